@@ -0,0 +1,375 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+};
+
+use clap::{Args, Parser, Subcommand};
+use lib::{
+    answers::input_key,
+    bench::BenchOptions,
+    client::Client,
+    error::{Error, Result},
+    examples::{extract, write_examples},
+    input::Input,
+    scaffold::scaffold,
+    solver::{bench, record, run, submit, verify, Answer, Part, Registry, Solver},
+    submit::Outcome,
+};
+
+#[derive(Parser)]
+#[command(about = "Runs the Advent of Code 2023 solutions")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Solve a day's puzzle, or every registered day when no day is given
+    Run {
+        #[command(flatten)]
+        selection: Selection,
+
+        /// Save the answers as the known correct ones of the input
+        #[arg(long)]
+        record: bool,
+    },
+
+    /// Check the recorded answers of a day, or of every registered day.
+    /// Fails when an answer does not match.
+    Verify {
+        /// Day to check
+        #[arg(long)]
+        day: Option<u8>,
+    },
+
+    /// Time the parsing and each part of a day's puzzle, or of every registered day
+    Bench {
+        #[command(flatten)]
+        selection: Selection,
+
+        /// Timed runs of each phase
+        #[arg(long, default_value_t = BenchOptions::default().iterations)]
+        iterations: usize,
+
+        /// Untimed runs of each phase before the timed ones
+        #[arg(long, default_value_t = BenchOptions::default().warmup)]
+        warmup: usize,
+
+        /// Print the results as JSON
+        #[arg(long)]
+        json: bool,
+    },
+
+    /// List the registered days
+    List,
+
+    /// Download the missing inputs of a day, or of every registered day.
+    /// Needs the AOC_SESSION environment variable.
+    Fetch {
+        /// Day to download
+        #[arg(long)]
+        day: Option<u8>,
+    },
+
+    /// Submit the answer of a part to the website, solving it when no answer
+    /// is given. Answers the submission history shows to be wrong are refused.
+    Submit {
+        /// Day of the puzzle
+        #[arg(long)]
+        day: u8,
+
+        /// Part to submit (1 or 2)
+        #[arg(long)]
+        part: Part,
+
+        /// Answer to submit instead of solving the puzzle
+        #[arg(long, conflicts_with = "input", value_parser = Answer::from_str)]
+        answer: Option<Answer>,
+
+        /// Input to solve, found like in `run` when not given
+        #[arg(long)]
+        input: Option<String>,
+    },
+
+    /// Save the examples of a saved puzzle page as inputs of the day, with
+    /// their answers recorded in its answers file
+    Examples {
+        /// Day of the puzzle
+        #[arg(long)]
+        day: u8,
+
+        /// HTML file of the puzzle page
+        #[arg(long)]
+        page: PathBuf,
+    },
+
+    /// Create the crate of a new day from the template
+    New {
+        /// Day to create
+        #[arg(long)]
+        day: u8,
+
+        /// Workspace directory
+        #[arg(long, default_value = ".")]
+        root: PathBuf,
+    },
+}
+
+/// Days, parts and input to run
+#[derive(Args)]
+struct Selection {
+    /// Day to solve
+    #[arg(long)]
+    day: Option<u8>,
+
+    /// Part to solve (1 or 2), both parts when not given
+    #[arg(long)]
+    part: Option<Part>,
+
+    /// Input file, `-` for stdin. When not given it is taken from the
+    /// AOC_INPUT environment variable or the day's inputs directory
+    #[arg(long, requires = "day")]
+    input: Option<String>,
+}
+
+impl Selection {
+    fn parts(&self) -> Vec<Part> {
+        match self.part {
+            Some(part) => vec![part],
+            None => Part::ALL.to_vec(),
+        }
+    }
+
+    /// Calls `f` with each selected solver and its input, which is downloaded
+    /// when missing, reporting the errors. Returns whether all of them succeeded.
+    fn for_each(
+        &self,
+        registry: &Registry,
+        mut f: impl FnMut(&dyn Solver, &Input) -> Result<()>,
+    ) -> bool {
+        let solvers = match self.day {
+            Some(day) => match registry.get(day) {
+                Some(solver) => vec![solver],
+                None => {
+                    eprintln!("error: no solver registered for day {}", day);
+                    return false;
+                }
+            },
+            None => registry.solvers().collect(),
+        };
+
+        let client = Client::from_env();
+        let mut success = true;
+        for solver in solvers {
+            let result = solver
+                .find_or_fetch_input(self.input.as_deref(), &client)
+                .and_then(|input| f(solver, &input));
+            if let Err(e) = result {
+                eprintln!("error: day {}: {}", solver.day(), e.report());
+                success = false;
+            }
+        }
+        success
+    }
+}
+
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    registry
+        .register(day_1::solver::Day1::default())
+        .register(day_2::solver::Day2::default())
+        .register(day_3::solver::Day3)
+        .register(day_4::solver::Day4);
+    registry
+}
+
+fn submit_answer(
+    solver: &dyn Solver,
+    part: Part,
+    answer: Option<Answer>,
+    input: Option<&str>,
+) -> Result<Outcome> {
+    let client = Client::from_env();
+    if let Some(answer) = answer {
+        return submit(solver, &client, part, answer, None);
+    }
+
+    let input = solver.find_or_fetch_input(input, &client)?;
+    let solved = run(solver, &[part], &input)?.remove(0);
+    submit(solver, &client, part, solved.answer, Some(&input))
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    let registry = registry();
+
+    let success = match cli.command {
+        Command::Run {
+            selection,
+            record: save,
+        } => {
+            let parts = selection.parts();
+            selection.for_each(&registry, |solver, input| {
+                let solved = run(solver, &parts, input)?;
+                if save {
+                    match input_key(input, Path::new(solver.inputs())) {
+                        Some(key) => record(solver, &key, &solved)?,
+                        None => eprintln!(
+                            "warning: only answers of the files in {} are recorded",
+                            solver.inputs()
+                        ),
+                    }
+                }
+                Ok(())
+            })
+        }
+        Command::Verify { day } => {
+            let solvers = match day {
+                Some(day) => registry.get(day).into_iter().collect(),
+                None => registry.solvers().collect::<Vec<&dyn Solver>>(),
+            };
+            if solvers.is_empty() {
+                eprintln!(
+                    "error: no solver registered for day {}",
+                    day.unwrap_or_default()
+                );
+                return ExitCode::FAILURE;
+            }
+
+            let mut success = true;
+            for solver in solvers {
+                match verify(solver) {
+                    Ok(passed) => success &= passed,
+                    Err(e) => {
+                        eprintln!("error: day {}: {}", solver.day(), e.report());
+                        success = false;
+                    }
+                }
+            }
+            success
+        }
+        Command::Bench {
+            selection,
+            iterations,
+            warmup,
+            json,
+        } => {
+            let parts = selection.parts();
+            let options = BenchOptions { iterations, warmup };
+            let mut reports = Vec::new();
+            let success = selection.for_each(&registry, |solver, input| {
+                let report = bench(solver, &parts, input, &options)?;
+                if !json {
+                    println!("{}", report);
+                }
+                reports.push(report);
+                Ok(())
+            });
+            if json {
+                match serde_json::to_string_pretty(&reports) {
+                    Ok(json) => println!("{}", json),
+                    Err(e) => {
+                        eprintln!("error: {}", e);
+                        return ExitCode::FAILURE;
+                    }
+                }
+            }
+            success
+        }
+        Command::List => {
+            for solver in registry.solvers() {
+                match solver.find_input(None) {
+                    Ok(input) => println!("Day {}: {}", solver.day(), input),
+                    Err(_) => println!("Day {}: no input found", solver.day()),
+                }
+            }
+            true
+        }
+        Command::Fetch { day } => {
+            let selection = Selection {
+                day,
+                part: None,
+                input: None,
+            };
+            selection.for_each(&registry, |solver, input| {
+                println!("Day {}: {}", solver.day(), input);
+                Ok(())
+            })
+        }
+        Command::Submit {
+            day,
+            part,
+            answer,
+            input,
+        } => {
+            let Some(solver) = registry.get(day) else {
+                eprintln!("error: no solver registered for day {}", day);
+                return ExitCode::FAILURE;
+            };
+            match submit_answer(solver, part, answer, input.as_deref()) {
+                Ok(outcome) => {
+                    println!("Day {} part {}: {}", day, part, outcome);
+                    outcome == Outcome::Correct
+                }
+                Err(e) => {
+                    eprintln!("error: day {}: {}", day, e.report());
+                    false
+                }
+            }
+        }
+        Command::Examples { day, page } => {
+            let Some(solver) = registry.get(day) else {
+                eprintln!("error: no solver registered for day {}", day);
+                return ExitCode::FAILURE;
+            };
+            let result = fs::read_to_string(&page)
+                .map_err(|source| Error::Open {
+                    input: page.display().to_string(),
+                    source,
+                })
+                .and_then(|html| write_examples(solver.crate_dir(), &extract(&html)));
+            match result {
+                Ok(written) if written.is_empty() => {
+                    eprintln!("error: no examples found in {}", page.display());
+                    false
+                }
+                Ok(written) => {
+                    for path in written {
+                        println!("Created {}", path.display());
+                    }
+                    true
+                }
+                Err(e) => {
+                    eprintln!("error: day {}: {}", day, e.report());
+                    false
+                }
+            }
+        }
+        Command::New { day, root } => match scaffold(&root, day) {
+            Ok(created) => {
+                for path in created {
+                    println!("Created {}", path.display());
+                }
+                println!(
+                    "\nTo run it from aoc, add `day-{day} = {{ path = \"../day-{day}\" }}` to \
+                     aoc/Cargo.toml and register `day_{day}::solver::Day{day}` in aoc/src/main.rs",
+                    day = day
+                );
+                true
+            }
+            Err(e) => {
+                eprintln!("error: {}", e.report());
+                false
+            }
+        },
+    };
+
+    if success {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
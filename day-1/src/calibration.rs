@@ -0,0 +1,321 @@
+use std::fmt;
+
+use lib::error::ParseError;
+use thiserror::Error;
+
+use crate::digits::{first_and_last, Token};
+
+/// How the calibration value of a line is built from its tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Rule {
+    /// First value times ten plus the last one, e.g. 3 and 12 give 42
+    #[default]
+    FirstLast,
+    /// Every value written one after the other
+    Concatenate,
+    /// Sum of every value
+    Sum,
+}
+
+/// Line where no calibration value could be computed
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalibrationError {
+    #[error("Line {line} has {found} numbers, at least {required} are required")]
+    TooFewNumbers {
+        line: usize,
+        found: usize,
+        required: usize,
+    },
+
+    #[error("The calibration value of line {line} is too large")]
+    Overflow { line: usize },
+}
+
+impl CalibrationError {
+    /// 1-based number of the line
+    pub fn line(&self) -> usize {
+        match self {
+            CalibrationError::TooFewNumbers { line, .. } | CalibrationError::Overflow { line } => {
+                *line
+            }
+        }
+    }
+}
+
+impl ParseError for CalibrationError {}
+
+/// Every line where no calibration value could be computed, in document order
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalibrationFailures {
+    pub errors: Vec<CalibrationError>,
+}
+
+impl fmt::Display for CalibrationFailures {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} lines have no calibration value", self.errors.len())?;
+        for error in self.errors.iter() {
+            write!(f, "\n  {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::error::Error for CalibrationFailures {}
+
+impl ParseError for CalibrationFailures {}
+
+/// Calibration value of a line of the document
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineValue {
+    /// 1-based line number
+    pub line: usize,
+    pub tokens: usize,
+    /// None when the line has too few tokens and was skipped
+    pub value: Option<u64>,
+}
+
+/// Calibration values of each line and their total
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Report {
+    pub lines: Vec<LineValue>,
+    pub total: u64,
+}
+
+impl Report {
+    /// Lines skipped because they have too few tokens
+    pub fn skipped(&self) -> impl Iterator<Item = &LineValue> {
+        self.lines.iter().filter(|line| line.value.is_none())
+    }
+}
+
+/// Computes calibration values from the tokens of each line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Formula {
+    rule: Rule,
+    min_tokens: usize,
+    strict: bool,
+}
+
+impl Default for Formula {
+    fn default() -> Self {
+        Self::new(Rule::default())
+    }
+}
+
+impl Formula {
+    /// Formula skipping the lines without any token
+    pub fn new(rule: Rule) -> Self {
+        Self {
+            rule,
+            min_tokens: 1,
+            strict: false,
+        }
+    }
+
+    /// Lines with fewer tokens are skipped, or are an error when strict
+    pub fn with_min_tokens(mut self, min_tokens: usize) -> Self {
+        self.min_tokens = min_tokens.max(1);
+        self
+    }
+
+    /// Whether lines with too few tokens are an error instead of being skipped
+    pub fn with_strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
+    /// Calibration value of the tokens of a line, None when there are too few of them
+    pub fn value(&self, line: usize, tokens: &[Token]) -> Result<Option<u64>, CalibrationError> {
+        if tokens.len() < self.min_tokens {
+            if !self.strict {
+                return Ok(None);
+            }
+            return Err(CalibrationError::TooFewNumbers {
+                line,
+                found: tokens.len(),
+                required: self.min_tokens,
+            });
+        }
+
+        let mut values = tokens.iter().map(|token| token.value as u64);
+        let value = match self.rule {
+            Rule::FirstLast => {
+                let (first, last) = first_and_last(tokens).expect("at least one token");
+                first_last(first.value as u64, last.value as u64)
+            }
+            Rule::Concatenate => concatenate(values),
+            Rule::Sum => values.try_fold(0u64, u64::checked_add),
+        };
+        value.map(Some).ok_or(CalibrationError::Overflow { line })
+    }
+
+    /// Calibration value of the tokens of a line, with the line number
+    pub fn line_value(&self, line: usize, tokens: &[Token]) -> Result<LineValue, CalibrationError> {
+        Ok(LineValue {
+            line,
+            tokens: tokens.len(),
+            value: self.value(line, tokens)?,
+        })
+    }
+
+    /// Calibration values of the tokens of each line, and their total
+    pub fn calibrate<'a>(
+        &self,
+        lines: impl IntoIterator<Item = &'a [Token]>,
+    ) -> Result<Report, CalibrationFailures> {
+        self.report(
+            lines
+                .into_iter()
+                .enumerate()
+                .map(|(index, tokens)| self.line_value(index + 1, tokens)),
+        )
+    }
+
+    /// Adds up the calibration values of each line. Fails with every line
+    /// without a value, not only the first one.
+    pub fn report(
+        &self,
+        values: impl IntoIterator<Item = Result<LineValue, CalibrationError>>,
+    ) -> Result<Report, CalibrationFailures> {
+        let mut report = Report {
+            lines: Vec::new(),
+            total: 0,
+        };
+        let mut errors = Vec::new();
+        for value in values {
+            let line = match value {
+                Ok(line) => line,
+                Err(error) => {
+                    errors.push(error);
+                    continue;
+                }
+            };
+            match report.total.checked_add(line.value.unwrap_or(0)) {
+                Some(total) => report.total = total,
+                None => errors.push(CalibrationError::Overflow { line: line.line }),
+            }
+            report.lines.push(line);
+        }
+        if !errors.is_empty() {
+            return Err(CalibrationFailures { errors });
+        }
+        Ok(report)
+    }
+}
+
+/// First value times ten plus the last one, None on overflow
+pub(crate) fn first_last(first: u64, last: u64) -> Option<u64> {
+    first.checked_mul(10)?.checked_add(last)
+}
+
+/// Numbers written one after the other, None on overflow
+fn concatenate(values: impl IntoIterator<Item = u64>) -> Option<u64> {
+    values.into_iter().try_fold(0u64, |number, value| {
+        let shift = 10u64.checked_pow(value.checked_ilog10().unwrap_or(0) + 1)?;
+        number.checked_mul(shift)?.checked_add(value)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        digits::{tokens, Overlaps},
+        vocabulary::Vocabulary,
+    };
+
+    use super::*;
+
+    fn lines(text: &str) -> Vec<Vec<Token>> {
+        let vocabulary = Vocabulary::english().with_word("dozen", 12);
+        text.lines()
+            .map(|line| tokens(line, &vocabulary, Overlaps::Include))
+            .collect()
+    }
+
+    fn total(formula: Formula, text: &str) -> Result<u64, CalibrationFailures> {
+        let lines = lines(text);
+        formula
+            .calibrate(lines.iter().map(Vec::as_slice))
+            .map(|report| report.total)
+    }
+
+    #[test]
+    fn applies_the_rules() {
+        let text = "two1nine\nxtwone3four\nabc\n7dozen";
+        assert_eq!(total(Formula::default(), text), Ok(29 + 24 + 82));
+        assert_eq!(
+            total(Formula::new(Rule::Concatenate), text),
+            Ok(219 + 2134 + 712)
+        );
+        assert_eq!(total(Formula::new(Rule::Sum), text), Ok(12 + 10 + 19));
+    }
+
+    #[test]
+    fn adds_the_last_value_to_ten_times_the_first_one() {
+        assert_eq!(total(Formula::default(), "7dozen"), Ok(82));
+        assert_eq!(total(Formula::new(Rule::Concatenate), "7dozen"), Ok(712));
+        assert_eq!(first_last(12, 3), Some(123));
+    }
+
+    #[test]
+    fn skips_or_reports_lines_with_too_few_numbers() {
+        let text = "two1nine\nabc\n4";
+        let lines = lines(text);
+        let report = Formula::default()
+            .with_min_tokens(2)
+            .calibrate(lines.iter().map(Vec::as_slice))
+            .unwrap();
+        assert_eq!(report.total, 29);
+        assert_eq!(
+            report.skipped().map(|line| line.line).collect::<Vec<_>>(),
+            [2, 3]
+        );
+        assert_eq!(
+            report.lines[0],
+            LineValue {
+                line: 1,
+                tokens: 3,
+                value: Some(29)
+            }
+        );
+
+        let failures = total(
+            Formula::default().with_min_tokens(2).with_strict(true),
+            text,
+        );
+        assert_eq!(
+            failures,
+            Err(CalibrationFailures {
+                errors: vec![
+                    CalibrationError::TooFewNumbers {
+                        line: 2,
+                        found: 0,
+                        required: 2
+                    },
+                    CalibrationError::TooFewNumbers {
+                        line: 3,
+                        found: 1,
+                        required: 2
+                    },
+                ]
+            })
+        );
+        assert_eq!(
+            failures.unwrap_err().to_string(),
+            "2 lines have no calibration value\n  \
+             Line 2 has 0 numbers, at least 2 are required\n  \
+             Line 3 has 1 numbers, at least 2 are required"
+        );
+    }
+
+    #[test]
+    fn reports_overflows() {
+        assert_eq!(
+            total(Formula::new(Rule::Concatenate), &"9".repeat(20)),
+            Err(CalibrationFailures {
+                errors: vec![CalibrationError::Overflow { line: 1 }]
+            })
+        );
+        assert_eq!(concatenate([1, 0, 23]), Some(1023));
+    }
+}
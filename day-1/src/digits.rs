@@ -1,8 +1,14 @@
+use std::cmp::Reverse;
+
+use lib::diagnostic::Span;
+
+use crate::vocabulary::Vocabulary;
+
 /// Returns the first numeric digit of a string (if present). Otherwise, returns None.
 pub fn get_first_digit(text: &str) -> Option<u8> {
     let mut digit = None;
     for c in text.chars() {
-        if c.is_digit(10) {
+        if c.is_ascii_digit() {
             if let Some(d) = c.to_digit(10) {
                 digit = Some(d as u8);
                 break;
@@ -14,80 +20,103 @@ pub fn get_first_digit(text: &str) -> Option<u8> {
 
 /// Returns the last numeric digit of a string (if present). Otherwise, returns None.
 pub fn get_last_digit(text: &str) -> Option<u8> {
-    get_first_digit(text.chars().rev().collect::<String>().as_str())
+    text.bytes()
+        .rev()
+        .find(u8::is_ascii_digit)
+        .map(|digit| digit - b'0')
 }
 
-const NUMBERS: [&str; 10] = [
-    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
-];
-const REVERSED_NUMBERS: [&str; 10] = [
-    "orez", "eno", "owt", "eerht", "ruof", "evif", "xis", "neves", "thgie", "enin",
-];
-
-/// Returns the first number of a string (if present). Otherwise, returns None.
-/// The number can be a numeric digit or the word of a number e.g. "one"
-fn find_number(text: &str, reference: &[&str]) -> Option<u8> {
-    let mut number = None;
-    // TODO: improve this with a trie instead of a vector
-    let mut candidates = Vec::<String>::new();
-
-    'outer: for c in text.chars() {
-        if c.is_digit(10) {
-            candidates = Vec::new();
-            if let Some(d) = c.to_digit(10) {
-                number = Some(d as u8);
-                break;
-            }
-        } else {
-            candidates.push(String::new());
-            for cand in candidates.iter_mut() {
-                cand.push(c);
-            }
+/// Numeric digit or word of a vocabulary found in a line
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token {
+    pub value: u32,
+    /// Bytes of the line
+    pub span: Span,
+}
 
-            candidates.retain(|cand| any_starts_with_text(cand, reference));
-            for cand in candidates.iter() {
-                if let Some(n) = get_number_from_word(cand, reference) {
-                    number = Some(n);
-                    break 'outer;
-                }
+/// Whether tokens sharing some bytes with an earlier token are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Overlaps {
+    /// Every token, e.g. both "two" and "one" in "twone"
+    Include,
+    /// Only the first of overlapping tokens, taking the longest when several start at the same place
+    Exclude,
+}
+
+/// Returns the numeric digits of a line as tokens
+pub fn digit_tokens(text: &str) -> Vec<Token> {
+    text.bytes()
+        .enumerate()
+        .filter(|(_, byte)| byte.is_ascii_digit())
+        .map(|(index, byte)| Token {
+            value: (byte - b'0') as u32,
+            span: Span::new(index, index + 1),
+        })
+        .collect()
+}
+
+/// Returns every token of a line ordered by where they start, the longest first
+pub fn tokens(text: &str, vocabulary: &Vocabulary, overlaps: Overlaps) -> Vec<Token> {
+    let mut tokens = vocabulary
+        .patterns()
+        .find_iter(text)
+        .map(|found| Token {
+            value: *found.value,
+            span: found.span,
+        })
+        .collect::<Vec<Token>>();
+    tokens.sort_by_key(|token| (token.span.start, Reverse(token.span.end)));
+
+    if overlaps == Overlaps::Exclude {
+        let mut end = 0;
+        tokens.retain(|token| {
+            let kept = token.span.start >= end;
+            if kept {
+                end = token.span.end;
             }
-        }
+            kept
+        });
     }
-
-    number
+    tokens
 }
 
-/// Returns true if any of the words in the reference array starts with the text.
-fn any_starts_with_text(text: &str, reference: &[&str]) -> bool {
-    for word in reference.iter() {
-        if word.starts_with(text) {
-            return true;
+/// Returns the first and last tokens of a list ordered by start: the first
+/// one and the one ending last, the longest when several end at the same place
+pub fn first_and_last(tokens: &[Token]) -> Option<(Token, Token)> {
+    let first = *tokens.first()?;
+    let last = tokens.iter().copied().reduce(|last, token| {
+        if (token.span.end, token.span.len()) > (last.span.end, last.span.len()) {
+            token
+        } else {
+            last
         }
-    }
-    false
+    })?;
+    Some((first, last))
 }
 
-/// Returns the first number of a string (digit or word) if present. Otherwise, returns None.
-pub fn get_first_number(text: &str) -> Option<u8> {
-    find_number(text, &NUMBERS)
+/// Returns the first number of a string (digit or word of the vocabulary) if present. Otherwise, returns None.
+pub fn get_first_number(text: &str, vocabulary: &Vocabulary) -> Option<u32> {
+    vocabulary
+        .patterns()
+        .first_in(text.bytes())
+        .map(|number| *number.value)
 }
 
-/// Returns the last number of a string (digit or word) if present. Otherwise, returns None.
-pub fn get_last_number(text: &str) -> Option<u8> {
-    let reversed_text = text.chars().rev().collect::<String>();
-    find_number(&reversed_text, &REVERSED_NUMBERS)
+/// Returns the last number of a string (digit or word of the vocabulary) if present. Otherwise, returns None.
+pub fn get_last_number(text: &str, vocabulary: &Vocabulary) -> Option<u32> {
+    vocabulary
+        .reversed_patterns()
+        .first_in(text.bytes().rev())
+        .map(|number| *number.value)
 }
 
-/// Returns the index of a word from the reference array if present. Otherwise, returns None.
-fn get_number_from_word(text: &str, reference: &[&str]) -> Option<u8> {
-    let mut number = None;
-    for (index, word) in reference.iter().enumerate() {
-        if word == &text {
-            number = Some(index as u8);
-            break;
-        }
-    }
-    number
+/// Returns the first and last numbers of a string (digit or word), found in a single pass.
+/// Number words may overlap, e.g. "eightwo" starts with 8 and ends with 2.
+pub fn get_first_and_last_number(text: &str, vocabulary: &Vocabulary) -> Option<(u32, u32)> {
+    vocabulary
+        .patterns()
+        .first_last(text)
+        .map(|(first, last)| (*first.value, *last.value))
 }
 
 #[cfg(test)]
@@ -112,50 +141,87 @@ mod tests {
 
     #[test]
     fn test_gets_number_from_word() {
-        assert_eq!(get_first_number("one"), Some(1));
-        assert_eq!(get_first_number("two"), Some(2));
-        assert_eq!(get_first_number("three2"), Some(3));
-        assert_eq!(get_first_number("2four"), Some(2));
-        assert_eq!(get_first_number("asvsd"), None);
-        assert_eq!(get_first_number("eno"), None);
+        let english = Vocabulary::english();
+        assert_eq!(get_first_number("one", &english), Some(1));
+        assert_eq!(get_first_number("two", &english), Some(2));
+        assert_eq!(get_first_number("three2", &english), Some(3));
+        assert_eq!(get_first_number("2four", &english), Some(2));
+        assert_eq!(get_first_number("asvsd", &english), None);
+        assert_eq!(get_first_number("eno", &english), None);
     }
 
     #[test]
     fn test_gets_last_number_from_word() {
-        assert_eq!(get_last_number("one"), Some(1));
-        assert_eq!(get_last_number("two"), Some(2));
-        assert_eq!(get_last_number("three2"), Some(2));
-        assert_eq!(get_last_number("fourthree"), Some(3));
-        assert_eq!(get_last_number("2abconeeigh"), Some(1));
+        let english = Vocabulary::english();
+        assert_eq!(get_last_number("one", &english), Some(1));
+        assert_eq!(get_last_number("two", &english), Some(2));
+        assert_eq!(get_last_number("three2", &english), Some(2));
+        assert_eq!(get_last_number("fourthree", &english), Some(3));
+        assert_eq!(get_last_number("2abconeeigh", &english), Some(1));
     }
 
     #[test]
-    fn test_any_starts_with() {
-        assert_eq!(any_starts_with_text("one", &NUMBERS), true);
-        assert_eq!(any_starts_with_text("two", &NUMBERS), true);
-        assert_eq!(any_starts_with_text("three2", &NUMBERS), false);
-        assert_eq!(any_starts_with_text("2four", &NUMBERS), false);
-        assert_eq!(any_starts_with_text("asvsd", &NUMBERS), false);
-        assert_eq!(any_starts_with_text("eig", &NUMBERS), true);
+    fn test_gets_overlapping_numbers() {
+        let english = Vocabulary::english();
+        assert_eq!(get_first_and_last_number("eightwo", &english), Some((8, 2)));
+        assert_eq!(
+            get_first_and_last_number("xtwone3four", &english),
+            Some((2, 4))
+        );
+        assert_eq!(
+            get_first_and_last_number("zoneight", &english),
+            Some((1, 8))
+        );
+        assert_eq!(get_first_and_last_number("7", &english), Some((7, 7)));
+        assert_eq!(get_first_and_last_number("eno", &english), None);
     }
 
     #[test]
-    fn test_get_number_from_word() {
-        assert_eq!(get_number_from_word("one", &NUMBERS), Some(1));
-        assert_eq!(get_number_from_word("two", &NUMBERS), Some(2));
-        assert_eq!(get_number_from_word("three2", &NUMBERS), None);
-        assert_eq!(get_number_from_word("2four", &NUMBERS), None);
-        assert_eq!(get_number_from_word("asvsd", &NUMBERS), None);
-        assert_eq!(get_number_from_word("eight", &NUMBERS), Some(8));
+    fn test_gets_numbers_of_other_vocabularies() {
+        let spanish = Vocabulary::spanish();
+        assert_eq!(
+            get_first_and_last_number("xdoseisx", &spanish),
+            Some((2, 6))
+        );
+        assert_eq!(get_first_number("two", &spanish), None);
+
+        let roman = Vocabulary::roman();
+        assert_eq!(
+            get_first_and_last_number("aXIVbXLIX", &roman),
+            Some((14, 49))
+        );
+        assert_eq!(get_last_number("VIII3", &roman), Some(3));
+
+        let custom = Vocabulary::english().with_word("dozen", 12);
+        assert_eq!(
+            get_first_and_last_number("dozenine", &custom),
+            Some((12, 9))
+        );
     }
 
     #[test]
-    fn test_get_number_from_reversed_word() {
-        assert_eq!(get_number_from_word("eno", &REVERSED_NUMBERS), Some(1));
-        assert_eq!(get_number_from_word("owt", &REVERSED_NUMBERS), Some(2));
-        assert_eq!(get_number_from_word("eerht2", &REVERSED_NUMBERS), None);
-        assert_eq!(get_number_from_word("2ruof", &REVERSED_NUMBERS), None);
-        assert_eq!(get_number_from_word("asvsd", &REVERSED_NUMBERS), None);
-        assert_eq!(get_number_from_word("thgie", &REVERSED_NUMBERS), Some(8));
+    fn test_tokens_with_spans() {
+        let english = Vocabulary::english();
+        let token = |value, start, end| Token {
+            value,
+            span: Span::new(start, end),
+        };
+        assert_eq!(
+            tokens("xtwone3", &english, Overlaps::Include),
+            [token(2, 1, 4), token(1, 3, 6), token(3, 6, 7)]
+        );
+        assert_eq!(
+            tokens("xtwone3", &english, Overlaps::Exclude),
+            [token(2, 1, 4), token(3, 6, 7)]
+        );
+        assert_eq!(
+            tokens("eightwo", &english, Overlaps::Include),
+            [token(8, 0, 5), token(2, 4, 7)]
+        );
+        assert_eq!(tokens("abc", &english, Overlaps::Include), []);
+
+        let roman = Vocabulary::roman();
+        assert_eq!(tokens("XIV", &roman, Overlaps::Exclude), [token(14, 0, 3)]);
+        assert_eq!(tokens("XIV", &roman, Overlaps::Include).len(), 6);
     }
 }
@@ -0,0 +1,289 @@
+use std::{io::Read, num::NonZeroUsize, thread};
+
+use lib::error::{Error, Result};
+use memchr::{memchr, memchr_iter, memrchr};
+
+use crate::{
+    calibration::{first_last, CalibrationError},
+    vocabulary::Vocabulary,
+};
+
+/// Size of the blocks of a file calibrated at once by [`calibrate_reader`]
+const BLOCK_SIZE: usize = 64 * 1024 * 1024;
+
+/// Sums of the calibration values of a document, computed like
+/// [`crate::solver::Day1`] with the default formula only
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Totals {
+    /// From numeric digits only, part 1
+    pub digits: u64,
+    /// From numeric digits and words of the vocabulary, part 2
+    pub numbers: u64,
+}
+
+impl Totals {
+    /// Adds the totals of a part of the document starting at the given line
+    fn add(&mut self, other: Totals, line: usize) -> Result<(), CalibrationError> {
+        let overflow = || CalibrationError::Overflow { line };
+        self.digits = self.digits.checked_add(other.digits).ok_or_else(overflow)?;
+        self.numbers = self
+            .numbers
+            .checked_add(other.numbers)
+            .ok_or_else(overflow)?;
+        Ok(())
+    }
+}
+
+/// First and last numeric digits of a line
+pub fn line_digits(line: &[u8]) -> Option<(u8, u8)> {
+    let first = line.iter().find(|byte| byte.is_ascii_digit())?;
+    let last = line.iter().rev().find(|byte| byte.is_ascii_digit())?;
+    Some((first - b'0', last - b'0'))
+}
+
+/// First and last numbers of a line (digit or word), reading the line forwards
+/// for the first one and backwards for the last one
+pub fn line_numbers(line: &[u8], vocabulary: &Vocabulary) -> Option<(u32, u32)> {
+    let first = vocabulary.patterns().first_in(line.iter().copied())?;
+    let last = vocabulary
+        .reversed_patterns()
+        .first_in(line.iter().rev().copied())?;
+    Some((*first.value, *last.value))
+}
+
+/// Calibrates the lines of a document, without allocating
+pub fn calibrate(text: &[u8], vocabulary: &Vocabulary) -> Result<Totals, CalibrationError> {
+    let mut totals = Totals::default();
+    let mut start = 0;
+    let ends = memchr_iter(b'\n', text).chain([text.len()]);
+    for (index, end) in ends.enumerate() {
+        if start >= text.len() {
+            break;
+        }
+        let line = &text[start..end];
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+        start = end + 1;
+
+        let value = |pair: Option<(u64, u64)>| match pair {
+            Some((first, last)) => {
+                first_last(first, last).ok_or(CalibrationError::Overflow { line: index + 1 })
+            }
+            None => Ok(0),
+        };
+        let line_totals = Totals {
+            digits: value(line_digits(line).map(|(first, last)| (first.into(), last.into())))?,
+            numbers: value(
+                line_numbers(line, vocabulary).map(|(first, last)| (first.into(), last.into())),
+            )?,
+        };
+        totals.add(line_totals, index + 1)?;
+    }
+    Ok(totals)
+}
+
+/// [`calibrate`] on chunks of the document in parallel, one per thread
+pub fn calibrate_parallel(
+    text: &[u8],
+    vocabulary: &Vocabulary,
+    threads: NonZeroUsize,
+) -> Result<Totals, CalibrationError> {
+    let chunks = chunks(text, threads.get());
+    let results = thread::scope(|scope| {
+        let handles = chunks
+            .iter()
+            .map(|chunk| scope.spawn(|| calibrate(chunk, vocabulary)))
+            .collect::<Vec<_>>();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().expect("calibration thread panicked"))
+            .collect::<Vec<_>>()
+    });
+
+    let mut totals = Totals::default();
+    let mut lines_before = 0;
+    for (chunk, result) in chunks.iter().zip(results) {
+        let chunk_totals = result.map_err(|error| shift_line(error, lines_before))?;
+        totals.add(chunk_totals, lines_before + 1)?;
+        lines_before += memchr_iter(b'\n', chunk).count();
+    }
+    Ok(totals)
+}
+
+/// Calibrates a document of any size, read in large blocks that are each
+/// calibrated in parallel
+pub fn calibrate_reader(
+    mut reader: impl Read,
+    vocabulary: &Vocabulary,
+    threads: NonZeroUsize,
+) -> Result<Totals> {
+    let mut totals = Totals::default();
+    // Grows with the data read, small inputs never need a whole block
+    let mut block = Vec::new();
+    let mut lines_before = 0;
+    loop {
+        let read = reader
+            .by_ref()
+            .take(BLOCK_SIZE as u64)
+            .read_to_end(&mut block)?;
+        let done = read == 0;
+        // Whole lines only, the rest is calibrated with the next block
+        let end = match memrchr(b'\n', &block) {
+            _ if done => block.len(),
+            Some(newline) => newline + 1,
+            // Line longer than a block, read on with the next one
+            None => continue,
+        };
+
+        let block_error = |error| line_error(&block[..end], lines_before, error);
+        let block_totals = calibrate_parallel(&block[..end], vocabulary, threads)
+            .map_err(|error| block_error(shift_line(error, lines_before)))?;
+        totals
+            .add(block_totals, lines_before + 1)
+            .map_err(block_error)?;
+        lines_before += memchr_iter(b'\n', &block[..end]).count();
+        block.drain(..end);
+        if done {
+            return Ok(totals);
+        }
+    }
+}
+
+/// Error of a line of a block of the document, shown with its text
+fn line_error(block: &[u8], lines_before: usize, error: CalibrationError) -> Error {
+    let line = block
+        .split(|byte| *byte == b'\n')
+        .nth(error.line() - lines_before - 1)
+        .unwrap_or_default();
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    Error::parse_line(error.line(), &String::from_utf8_lossy(line), error)
+}
+
+/// Makes the line of an error relative to the whole document
+fn shift_line(error: CalibrationError, lines_before: usize) -> CalibrationError {
+    match error {
+        CalibrationError::Overflow { line } => CalibrationError::Overflow {
+            line: lines_before + line,
+        },
+        CalibrationError::TooFewNumbers {
+            line,
+            found,
+            required,
+        } => CalibrationError::TooFewNumbers {
+            line: lines_before + line,
+            found,
+            required,
+        },
+    }
+}
+
+/// Splits a document into about `count` chunks of whole lines
+fn chunks(text: &[u8], count: usize) -> Vec<&[u8]> {
+    let size = text.len().div_ceil(count).max(1);
+    let mut chunks = Vec::with_capacity(count);
+    let mut rest = text;
+    while !rest.is_empty() {
+        let end = match memchr(b'\n', &rest[size.min(rest.len())..]) {
+            Some(newline) => size + newline + 1,
+            None => rest.len(),
+        };
+        let (chunk, after) = rest.split_at(end);
+        chunks.push(chunk);
+        rest = after;
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{calibration::Formula, solver::CalibrationLine};
+
+    use super::*;
+
+    /// Lines mixing digits, overlapping words and lines without numbers
+    fn document(lines: usize) -> String {
+        const PARTS: [&str; 9] = [
+            "eightwo", "x", "3", "twone", "nine", "abc", "7", "oneight", "\r",
+        ];
+        let mut seed = 42u64;
+        let mut document = String::new();
+        for _ in 0..lines {
+            for _ in 0..seed % 5 {
+                seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1);
+                document.push_str(PARTS[(seed >> 33) as usize % PARTS.len()]);
+            }
+            document.push('\n');
+        }
+        document
+    }
+
+    /// Totals of the per-line path of the solver
+    fn expected(document: &str) -> Totals {
+        let (english, formula) = (Vocabulary::english(), Formula::default());
+        let lines = document
+            .lines()
+            .enumerate()
+            .map(|(index, line)| CalibrationLine::parse(index + 1, line, &english, &formula))
+            .collect::<Vec<_>>();
+        Totals {
+            digits: formula
+                .report(lines.iter().map(|line| line.digits))
+                .unwrap()
+                .total,
+            numbers: formula
+                .report(lines.iter().map(|line| line.numbers))
+                .unwrap()
+                .total,
+        }
+    }
+
+    #[test]
+    fn matches_the_line_functions() {
+        let english = Vocabulary::english();
+        assert_eq!(line_digits(b"a1b2c3"), Some((1, 3)));
+        assert_eq!(line_digits(b"abc"), None);
+        assert_eq!(line_numbers(b"xtwone3four", &english), Some((2, 4)));
+        assert_eq!(line_numbers(b"eightwo", &english), Some((8, 2)));
+        assert_eq!(line_numbers(b"eno", &english), None);
+
+        let roman = Vocabulary::roman();
+        assert_eq!(line_numbers(b"aXIVbXLIX", &roman), Some((14, 49)));
+    }
+
+    #[test]
+    fn gives_the_same_totals_as_the_solver() {
+        let document = document(2_000);
+        let totals = expected(&document);
+        let english = Vocabulary::english();
+        assert_eq!(calibrate(document.as_bytes(), &english), Ok(totals));
+        for threads in [1, 3, 8] {
+            let threads = NonZeroUsize::new(threads).unwrap();
+            assert_eq!(
+                calibrate_parallel(document.as_bytes(), &english, threads),
+                Ok(totals)
+            );
+            let read = calibrate_reader(document.as_bytes(), &english, threads).unwrap();
+            assert_eq!(read, totals);
+        }
+
+        let unterminated = "two1\n\n3x";
+        assert_eq!(
+            calibrate(unterminated.as_bytes(), &english),
+            Ok(expected(unterminated))
+        );
+    }
+
+    #[test]
+    fn reports_errors_with_the_line_in_the_document() {
+        assert_eq!(
+            shift_line(CalibrationError::Overflow { line: 1 }, 1_000),
+            CalibrationError::Overflow { line: 1_001 }
+        );
+        let document = "1\n".repeat(1_000);
+        let chunks = chunks(document.as_bytes(), 4);
+        let lines = chunks
+            .iter()
+            .map(|chunk| memchr_iter(b'\n', chunk).count())
+            .sum::<usize>();
+        assert_eq!((chunks.len(), lines), (4, 1_000));
+    }
+}
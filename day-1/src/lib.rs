@@ -1 +1,5 @@
+pub mod calibration;
 pub mod digits;
+pub mod fast;
+pub mod solver;
+pub mod vocabulary;
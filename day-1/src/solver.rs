@@ -0,0 +1,204 @@
+use std::{io::BufRead, num::NonZeroUsize, thread};
+
+use lib::{
+    error::{Error, Result},
+    input::{try_for_each_line, LineReader, LineSource},
+    solver::Solution,
+};
+
+use crate::{
+    calibration::{CalibrationError, CalibrationFailures, Formula, LineValue},
+    digits::{digit_tokens, tokens, Overlaps},
+    fast::{calibrate_reader, Totals},
+    vocabulary::Vocabulary,
+};
+
+/// Calibration values of a line of the document, computed while it is read
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CalibrationLine {
+    /// From numeric digits only
+    pub digits: Result<LineValue, CalibrationError>,
+    /// From numeric digits or words of the vocabulary, overlapping ones included
+    pub numbers: Result<LineValue, CalibrationError>,
+    /// The line itself, only kept to report it when a value is missing
+    pub text: Option<String>,
+}
+
+impl CalibrationLine {
+    pub fn parse(
+        line_number: usize,
+        line: &str,
+        vocabulary: &Vocabulary,
+        formula: &Formula,
+    ) -> Self {
+        let digits = formula.line_value(line_number, &digit_tokens(line));
+        let numbers = formula.line_value(line_number, &tokens(line, vocabulary, Overlaps::Include));
+        Self {
+            text: (digits.is_err() || numbers.is_err()).then(|| line.to_string()),
+            digits,
+            numbers,
+        }
+    }
+}
+
+/// Error of the lines without a calibration value, showing the first of them
+fn failure(lines: &[CalibrationLine], failures: CalibrationFailures) -> Error {
+    let line_number = failures.errors.first().map_or(0, CalibrationError::line);
+    let text = line_number
+        .checked_sub(1)
+        .and_then(|index| lines.get(index)?.text.as_deref())
+        .unwrap_or_default();
+    Error::parse_line(line_number, text, failures)
+}
+
+/// Calibration document, read with the fastest path the formula allows
+#[derive(Debug, PartialEq, Eq)]
+pub enum Calibration {
+    /// Totals of both parts, computed in parallel from the raw input with the
+    /// default formula
+    Totals(Totals),
+    /// Values of each line, with any other formula
+    Lines(Vec<CalibrationLine>),
+}
+
+/// Trebuchet calibration
+#[derive(Default)]
+pub struct Day1 {
+    /// Words read as numbers in part 2, English number words by default
+    pub vocabulary: Vocabulary,
+    /// Calibration value of each line, from its first and last numbers by default
+    pub formula: Formula,
+}
+
+impl Solution for Day1 {
+    type Input = Calibration;
+    type PartOne = u64;
+    type PartTwo = u64;
+
+    const DAY: u8 = 1;
+    const INPUTS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/inputs");
+
+    fn parse(&self, lines: &mut dyn LineSource) -> Result<Self::Input> {
+        let mut calibration = Vec::new();
+        try_for_each_line(lines, |line_number, line| {
+            calibration.push(CalibrationLine::parse(
+                line_number,
+                line,
+                &self.vocabulary,
+                &self.formula,
+            ));
+            Ok::<(), Error>(())
+        })?;
+        Ok(Calibration::Lines(calibration))
+    }
+
+    fn parse_reader(&self, reader: &mut dyn BufRead) -> Result<Self::Input> {
+        if self.formula != Formula::default() {
+            return self.parse(&mut LineReader::new(reader));
+        }
+        let threads = thread::available_parallelism().unwrap_or(NonZeroUsize::MIN);
+        let totals = calibrate_reader(reader, &self.vocabulary, threads)?;
+        Ok(Calibration::Totals(totals))
+    }
+
+    /// Sum of the calibration values built from the numeric digits of each line
+    fn part_one(&self, calibration: &Self::Input) -> Result<Self::PartOne> {
+        match calibration {
+            Calibration::Totals(totals) => Ok(totals.digits),
+            Calibration::Lines(lines) => {
+                let digits = lines.iter().map(|line| line.digits);
+                let report = self.formula.report(digits);
+                Ok(report.map_err(|failures| failure(lines, failures))?.total)
+            }
+        }
+    }
+
+    /// Sum of the calibration values built from the numbers (digit or word) of each line
+    fn part_two(&self, calibration: &Self::Input) -> Result<Self::PartTwo> {
+        match calibration {
+            Calibration::Totals(totals) => Ok(totals.numbers),
+            Calibration::Lines(lines) => {
+                let numbers = lines.iter().map(|line| line.numbers);
+                let report = self.formula.report(numbers);
+                Ok(report.map_err(|failures| failure(lines, failures))?.total)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::calibration::Rule;
+
+    use super::*;
+
+    /// Example from the Advent of Code website
+    /// https://adventofcode.com/2023/day/1
+    #[test]
+    fn website_example_part_1() {
+        let lines = "1abc2\n\
+             pqr3stu8vwx\n\
+             a1b2c3d4e5f\n\
+             treb7uchet";
+        let day = Day1::default();
+        let calibration = day.parse(&mut lines.lines()).unwrap();
+        assert_eq!(day.part_one(&calibration).unwrap(), 142);
+    }
+
+    /// Example from the Advent of Code website
+    /// https://adventofcode.com/2023/day/1#part2
+    #[test]
+    fn website_example_part_2() {
+        let lines = "two1nine\n\
+             eightwothree\n\
+             abcone2threexyz\n\
+             xtwone3four\n\
+             4nineeightseven2\n\
+             zoneight234\n\
+             7pqrstsixteen";
+        let day = Day1::default();
+        let calibration = day.parse(&mut lines.lines()).unwrap();
+        assert_eq!(day.part_two(&calibration).unwrap(), 281);
+    }
+
+    #[test]
+    fn reads_lines_one_by_one_with_other_formulas() {
+        let lines = "two1nine\nabc\nxtwone3four";
+        let day = Day1::default();
+        let calibration = day.parse_reader(&mut lines.as_bytes()).unwrap();
+        assert!(matches!(calibration, Calibration::Totals(_)));
+        assert_eq!(day.part_two(&calibration).unwrap(), 29 + 24);
+        let calibration = day.parse(&mut lines.lines()).unwrap();
+        assert!(matches!(calibration, Calibration::Lines(_)));
+        assert_eq!(day.part_two(&calibration).unwrap(), 29 + 24);
+
+        let day = Day1 {
+            formula: Formula::new(Rule::Sum),
+            ..Day1::default()
+        };
+        let calibration = day.parse_reader(&mut lines.as_bytes()).unwrap();
+        assert!(matches!(calibration, Calibration::Lines(_)));
+        assert_eq!(day.part_two(&calibration).unwrap(), 12 + 10);
+    }
+
+    #[test]
+    fn reports_every_line_without_a_value_when_strict() {
+        let day = Day1 {
+            formula: Formula::default().with_strict(true),
+            ..Day1::default()
+        };
+        let calibration = day.parse(&mut "1abc2\nabc\none\nxyz".lines()).unwrap();
+        assert_eq!(
+            day.part_one(&calibration).unwrap_err().report(),
+            "Invalid input at line 2, column 1\n  \
+             caused by: 3 lines have no calibration value\n  \
+             Line 2 has 0 numbers, at least 1 are required\n  \
+             Line 3 has 0 numbers, at least 1 are required\n  \
+             Line 4 has 0 numbers, at least 1 are required\n  \
+             |\n\
+             2 | abc\n  \
+             | ^^^"
+        );
+        assert!(day.part_two(&calibration).is_err());
+    }
+}
@@ -0,0 +1,212 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use lib::{
+    error::{Error, Result, ResultExt},
+    parse::Segment,
+    patterns::PatternSet,
+};
+
+const ENGLISH: [&str; 10] = [
+    "zero", "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+const SPANISH: [&str; 10] = [
+    "cero", "uno", "dos", "tres", "cuatro", "cinco", "seis", "siete", "ocho", "nueve",
+];
+
+/// Names of the built-in vocabularies, see [`Vocabulary::builtin`]
+pub const BUILTIN: [&str; 3] = ["english", "spanish", "roman"];
+
+/// Words read as numbers in a calibration document. Numeric digits are
+/// always read as their value, whatever the vocabulary.
+#[derive(Debug, Clone)]
+pub struct Vocabulary {
+    words: BTreeMap<String, u32>,
+    patterns: PatternSet<u32>,
+    /// Patterns written backwards, to search lines from their end
+    reversed: PatternSet<u32>,
+}
+
+impl Vocabulary {
+    pub fn new(words: impl IntoIterator<Item = (impl Into<String>, u32)>) -> Self {
+        Self::from_map(
+            words
+                .into_iter()
+                .map(|(word, value)| (word.into(), value))
+                .collect(),
+        )
+    }
+
+    fn from_map(words: BTreeMap<String, u32>) -> Self {
+        let digits = (0..10).map(|digit| (digit.to_string().into_bytes(), digit));
+        let patterns = digits
+            .chain(
+                words
+                    .iter()
+                    .map(|(word, value)| (word.clone().into_bytes(), *value)),
+            )
+            .collect::<Vec<(Vec<u8>, u32)>>();
+        let reversed = patterns.iter().map(|(pattern, value)| {
+            let mut pattern = pattern.clone();
+            pattern.reverse();
+            (pattern, *value)
+        });
+        Self {
+            reversed: PatternSet::new(reversed),
+            patterns: PatternSet::new(patterns),
+            words,
+        }
+    }
+
+    /// English number words, from "zero" to "nine"
+    pub fn english() -> Self {
+        Self::new(ENGLISH.into_iter().zip(0..))
+    }
+
+    /// Spanish number words, from "cero" to "nueve"
+    pub fn spanish() -> Self {
+        Self::new(SPANISH.into_iter().zip(0..))
+    }
+
+    /// Uppercase Roman numerals, from "I" to "C"
+    pub fn roman() -> Self {
+        Self::new((1..=100).map(|value| (roman_numeral(value), value)))
+    }
+
+    /// Built-in vocabulary by name, one of [`BUILTIN`]
+    pub fn builtin(name: &str) -> Option<Self> {
+        match name {
+            "english" => Some(Self::english()),
+            "spanish" => Some(Self::spanish()),
+            "roman" => Some(Self::roman()),
+            _ => None,
+        }
+    }
+
+    /// Adds a word, or changes the value of a known one
+    pub fn with_word(self, word: impl Into<String>, value: u32) -> Self {
+        let mut words = self.words;
+        words.insert(word.into(), value);
+        Self::from_map(words)
+    }
+
+    /// Reads a vocabulary file, see [`Vocabulary::parse`]
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let text = fs::read_to_string(path).map_err(|source| Error::Open {
+            input: path.display().to_string(),
+            source,
+        })?;
+        Self::parse(&text)
+    }
+
+    /// Reads a `<word> = <value>` entry per line. Empty lines and lines
+    /// starting with `#` are ignored.
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut words = BTreeMap::new();
+        for (index, line) in text.lines().enumerate() {
+            let entry = Segment::new(line).trim();
+            if entry.is_empty() || entry.as_str().starts_with('#') {
+                continue;
+            }
+            let (word, value) = entry.key_value('=').at_line(index + 1, line)?;
+            let value = value.parse("a number").at_line(index + 1, line)?;
+            words.insert(word.as_str().to_string(), value);
+        }
+        Ok(Self::from_map(words))
+    }
+
+    /// Value of a word of the vocabulary
+    pub fn value(&self, word: &str) -> Option<u32> {
+        self.words.get(word).copied()
+    }
+
+    /// Words and their values, in alphabetical order
+    pub fn words(&self) -> impl Iterator<Item = (&str, u32)> {
+        self.words
+            .iter()
+            .map(|(word, value)| (word.as_str(), *value))
+    }
+
+    /// Numeric digits and words of the vocabulary, with their values
+    pub fn patterns(&self) -> &PatternSet<u32> {
+        &self.patterns
+    }
+
+    /// Same as [`Vocabulary::patterns`], written backwards
+    pub fn reversed_patterns(&self) -> &PatternSet<u32> {
+        &self.reversed
+    }
+}
+
+impl Default for Vocabulary {
+    fn default() -> Self {
+        Self::english()
+    }
+}
+
+fn roman_numeral(mut value: u32) -> String {
+    const SYMBOLS: [(u32, &str); 9] = [
+        (100, "C"),
+        (90, "XC"),
+        (50, "L"),
+        (40, "XL"),
+        (10, "X"),
+        (9, "IX"),
+        (5, "V"),
+        (4, "IV"),
+        (1, "I"),
+    ];
+    let mut numeral = String::new();
+    for (symbol_value, symbol) in SYMBOLS {
+        while value >= symbol_value {
+            numeral.push_str(symbol);
+            value -= symbol_value;
+        }
+    }
+    numeral
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn builds_the_builtin_vocabularies() {
+        assert_eq!(Vocabulary::english().value("seven"), Some(7));
+        assert_eq!(Vocabulary::spanish().value("nueve"), Some(9));
+        assert_eq!(Vocabulary::roman().value("XLIX"), Some(49));
+        assert_eq!(Vocabulary::roman().words().count(), 100);
+        assert!(BUILTIN
+            .iter()
+            .all(|name| Vocabulary::builtin(name).is_some()));
+        assert!(Vocabulary::builtin("klingon").is_none());
+    }
+
+    #[test]
+    fn adds_custom_words() {
+        let vocabulary = Vocabulary::english()
+            .with_word("ten", 10)
+            .with_word("dozen", 12);
+        assert_eq!(vocabulary.value("dozen"), Some(12));
+        assert_eq!(vocabulary.words().count(), 12);
+    }
+
+    #[test]
+    fn parses_vocabulary_files() {
+        let vocabulary = Vocabulary::parse("# German\neins = 1\n\n  zwei=2 \n").unwrap();
+        assert_eq!(
+            vocabulary.words().collect::<Vec<_>>(),
+            [("eins", 1), ("zwei", 2)]
+        );
+
+        let error = Vocabulary::parse("eins = 1\nzwei = two").unwrap_err();
+        assert_eq!(
+            error.report(),
+            "Invalid input at line 2, column 8\n  \
+             caused by: Expected a number, found \"two\"\n  \
+             |\n\
+             2 | zwei = two\n  \
+             |        ^^^"
+        );
+    }
+}
@@ -0,0 +1,8 @@
+use std::process::ExitCode;
+
+use day_1::solver::Day1;
+use lib::fixtures::run_tests;
+
+fn main() -> ExitCode {
+    run_tests(Day1::default())
+}
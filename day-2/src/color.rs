@@ -1,135 +1,354 @@
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    ops::{Add, Sub},
+    sync::Mutex,
+};
+
+use lib::{
+    diagnostic::Span,
+    error::{Error, ParseError},
+    parse::{Segment, SyntaxError},
+};
+use serde::{Serialize, Serializer};
 use thiserror::Error;
 
+/// Parsing errors, with the span of the invalid part of the parsed line
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum ColorParserError {
-    #[error("Invalid color count record: {0}")]
-    InvalidColorCount(String),
-
     #[error("Invalid color: {0}")]
-    InvalidColor(String),
+    InvalidColor(String, Span),
+
+    #[error(transparent)]
+    Syntax(#[from] SyntaxError),
+}
+
+impl ParseError for ColorParserError {
+    fn span(&self) -> Option<Span> {
+        match self {
+            ColorParserError::InvalidColor(_, span) => Some(*span),
+            ColorParserError::Syntax(error) => error.span(),
+        }
+    }
+}
+
+/// More cubes of a color taken than available
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("{taken} {color} cubes taken, only {available} available")]
+pub struct ColorUnderflow {
+    pub color: Color,
+    pub available: ColorCount,
+    pub taken: ColorCount,
+}
+
+impl From<ColorParserError> for Error {
+    fn from(error: ColorParserError) -> Self {
+        Error::parse(error)
+    }
+}
+
+/// Name of a cube color. Names are interned, so colors are cheap to copy and
+/// parsing does not allocate for the colors already seen. Interned names are
+/// never freed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Color(&'static str);
+
+impl Color {
+    pub const RED: Color = Color("red");
+    pub const GREEN: Color = Color("green");
+    pub const BLUE: Color = Color("blue");
+
+    pub fn new(name: &str) -> Self {
+        static NAMES: Mutex<BTreeSet<&'static str>> = Mutex::new(BTreeSet::new());
+        let mut names = NAMES.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(interned) = names.get(name) {
+            return Color(interned);
+        }
+        let interned: &'static str = Box::leak(name.into());
+        names.insert(interned);
+        Color(interned)
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.0
+    }
+}
+
+impl Serialize for Color {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.0)
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0)
+    }
+}
+
+/// Colors that the cubes can have. A declared palette rejects the other
+/// colors, an inferred one learns every color it is asked about.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Palette {
+    colors: BTreeSet<Color>,
+    inferred: bool,
+}
+
+impl Palette {
+    pub fn declared(names: impl IntoIterator<Item = impl AsRef<str>>) -> Self {
+        Self {
+            colors: names
+                .into_iter()
+                .map(|name| Color::new(name.as_ref()))
+                .collect(),
+            inferred: false,
+        }
+    }
+
+    /// Empty palette learning the colors of the input
+    pub fn inferred() -> Self {
+        Self {
+            colors: BTreeSet::new(),
+            inferred: true,
+        }
+    }
+
+    /// Red, green and blue
+    pub fn rgb() -> Self {
+        Self {
+            colors: BTreeSet::from([Color::RED, Color::GREEN, Color::BLUE]),
+            inferred: false,
+        }
+    }
+
+    pub fn is_inferred(&self) -> bool {
+        self.inferred
+    }
+
+    pub fn contains(&self, color: Color) -> bool {
+        self.colors.contains(&color)
+    }
 
-    #[error("Invalid count: {0}")]
-    InvalidCount(String),
+    /// Colors of the palette, sorted by name
+    pub fn colors(&self) -> impl Iterator<Item = Color> + '_ {
+        self.colors.iter().copied()
+    }
+
+    /// Color of the given name, learnt by inferred palettes when it is new
+    pub fn resolve(&mut self, name: &str) -> Option<Color> {
+        if let Some(color) = self.colors.iter().find(|color| color.name() == name) {
+            return Some(*color);
+        }
+        if !self.inferred {
+            return None;
+        }
+        let color = Color::new(name);
+        self.colors.insert(color);
+        Some(color)
+    }
 }
 
-#[derive(Debug, PartialEq, Eq)]
-pub enum Color {
-    Red,
-    Green,
-    Blue,
+impl Default for Palette {
+    fn default() -> Self {
+        Self::rgb()
+    }
 }
 
-type ColorCount = u16;
+pub type ColorCount = u16;
 
-#[derive(Debug, PartialEq, Eq)]
+/// Count of cubes of each color, colors without a count are not in the set.
+/// Sets are compared as if those colors had a count of 0.
+#[derive(Debug, Default, Clone)]
 pub struct ColorSet {
-    pub red: Option<ColorCount>,
-    pub green: Option<ColorCount>,
-    pub blue: Option<ColorCount>,
+    counts: BTreeMap<Color, ColorCount>,
 }
 
 impl ColorSet {
+    /// Set of red, green and blue cubes
     pub fn new(
         red: Option<ColorCount>,
         green: Option<ColorCount>,
         blue: Option<ColorCount>,
     ) -> Self {
-        Self { red, green, blue }
+        [
+            (Color::RED, red),
+            (Color::GREEN, green),
+            (Color::BLUE, blue),
+        ]
+        .into_iter()
+        .filter_map(|(color, count)| Some((color, count?)))
+        .collect()
     }
 
-    pub fn default() -> Self {
-        Self {
-            red: None,
-            green: None,
-            blue: None,
-        }
+    /// Parses color counts like `1 red, 2 green`, with the colors of the palette
+    pub fn parse(color_counts_str: &str, palette: &mut Palette) -> Result<Self, ColorParserError> {
+        Self::parse_segment(Segment::new(color_counts_str), palette)
     }
 
-    pub fn parse(color_counts_str: &str) -> Result<Self, ColorParserError> {
-        let mut color_records = color_counts_str.split(",");
+    /// Parses the color counts of a part of a line, like `1 red, 2 green`
+    pub fn parse_segment(
+        color_counts: Segment,
+        palette: &mut Palette,
+    ) -> Result<Self, ColorParserError> {
         let mut result = ColorSet::default();
+        for record in color_counts.split(',') {
+            let (name, count) = parse_color_count(record)?;
+            let color = palette.resolve(name.as_str()).ok_or_else(|| {
+                ColorParserError::InvalidColor(name.as_str().to_string(), name.span())
+            })?;
+            result.set(color, Some(count));
+        }
+        Ok(result)
+    }
 
-        color_records.try_for_each(|record| match parse_color_count(record) {
-            Ok((color, count)) => {
-                match color {
-                    "red" => result.red = Some(count),
+    /// Whether the set has a count for every color of the palette
+    pub fn contains_all(&self, palette: &Palette) -> bool {
+        palette
+            .colors()
+            .all(|color| self.counts.contains_key(&color))
+    }
+
+    pub fn get(&self, color: Color) -> Option<ColorCount> {
+        self.counts.get(&color).copied()
+    }
+
+    pub fn set(&mut self, color: Color, count: Option<ColorCount>) {
+        match count {
+            Some(count) => self.counts.insert(color, count),
+            None => self.counts.remove(&color),
+        };
+    }
+
+    /// Colors with a count, sorted by name
+    pub fn colors(&self) -> impl Iterator<Item = (Color, ColorCount)> + '_ {
+        self.counts.iter().map(|(color, count)| (*color, *count))
+    }
+
+    /// Whether every color of the other set has at most as many cubes in
+    /// this one, see [`PartialOrd`]
+    pub fn covers(&self, other: &Self) -> bool {
+        self >= other
+    }
+
+    /// Largest count of each color of either set
+    pub fn join(&self, other: &Self) -> Self {
+        self.merge(other, |count, other| count.max(other))
+    }
+
+    /// Smallest count of each color of both sets
+    pub fn meet(&self, other: &Self) -> Self {
+        self.counts
+            .iter()
+            .filter_map(|(color, count)| Some((*color, (*count).min(other.get(*color)?))))
+            .collect()
+    }
+
+    /// Cubes of both sets, None on overflow
+    pub fn checked_add(&self, other: &Self) -> Option<Self> {
+        let mut result = self.clone();
+        for (color, count) in other.colors() {
+            let sum = result.get(color).unwrap_or(0).checked_add(count)?;
+            result.set(color, Some(sum));
+        }
+        Some(result)
+    }
 
-                    "green" => result.green = Some(count),
-                    "blue" => result.blue = Some(count),
-                    _ => return Err(ColorParserError::InvalidColor(color.to_string())),
+    /// Cubes left after taking the other set out of this one
+    pub fn checked_sub(&self, other: &Self) -> Result<Self, ColorUnderflow> {
+        let mut result = self.clone();
+        for (color, count) in other.colors() {
+            match result.get(color) {
+                Some(available) if available >= count => result.set(color, Some(available - count)),
+                None if count == 0 => {}
+                available => {
+                    return Err(ColorUnderflow {
+                        color,
+                        available: available.unwrap_or(0),
+                        taken: count,
+                    })
                 }
-                Ok(())
             }
-            Err(e) => Err(e),
-        })?;
+        }
         Ok(result)
     }
 
-    pub fn all_some(&self) -> bool {
-        self.red.is_some() && self.green.is_some() && self.blue.is_some()
+    fn merge(&self, other: &Self, f: impl Fn(ColorCount, ColorCount) -> ColorCount) -> Self {
+        let mut result = self.clone();
+        for (color, count) in other.colors() {
+            let merged = result.get(color).map_or(count, |current| f(current, count));
+            result.set(color, Some(merged));
+        }
+        result
     }
 
-    pub fn get(&self, color: &Color) -> Option<ColorCount> {
-        match color {
-            Color::Red => self.red,
-            Color::Green => self.green,
-            Color::Blue => self.blue,
+    /// Count of each color multiplied together, 0 for an empty set
+    pub fn power(&self) -> u64 {
+        if self.counts.is_empty() {
+            return 0;
         }
+        self.counts.values().map(|count| *count as u64).product()
     }
+}
 
-    pub fn set(&mut self, color: &Color, count: Option<ColorCount>) {
-        match color {
-            Color::Red => self.red = count,
-            Color::Green => self.green = count,
-            Color::Blue => self.blue = count,
+impl FromIterator<(Color, ColorCount)> for ColorSet {
+    fn from_iter<I: IntoIterator<Item = (Color, ColorCount)>>(iter: I) -> Self {
+        Self {
+            counts: iter.into_iter().collect(),
         }
     }
+}
 
-    pub fn gt_color(&self, other: &Self, color: &Color) -> bool {
-        self.get(color).unwrap_or(0) > other.get(color).unwrap_or(0)
+impl PartialEq for ColorSet {
+    fn eq(&self, other: &Self) -> bool {
+        self.partial_cmp(other) == Some(Ordering::Equal)
     }
+}
+
+impl Eq for ColorSet {}
 
-    /// Determines if this color count is greater than the other color count
-    /// for all colors
-    pub fn gt(&self, other: &Self) -> bool {
-        self.gt_color(other, &Color::Red)
-            && self.gt_color(other, &Color::Green)
-            && self.gt_color(other, &Color::Blue)
+/// Dominance order: a set is smaller than another when it has at most as
+/// many cubes of every color, a color without a count having none. Sets where
+/// each one has more cubes of some color are not comparable.
+impl PartialOrd for ColorSet {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        let count = |set: &Self, color| set.get(color).unwrap_or(0);
+        let mut ordering = Ordering::Equal;
+        for color in self.counts.keys().chain(other.counts.keys()) {
+            match (ordering, count(self, *color).cmp(&count(other, *color))) {
+                (_, Ordering::Equal) => {}
+                (Ordering::Equal, color_ordering) => ordering = color_ordering,
+                (ordering, color_ordering) if ordering != color_ordering => return None,
+                _ => {}
+            }
+        }
+        Some(ordering)
     }
+}
+
+impl Add for &ColorSet {
+    type Output = ColorSet;
 
-    /// Count of each color multiplied together
-    pub fn power(&self) -> u32 {
-        self.red.unwrap_or(0) as u32
-            * self.green.unwrap_or(0) as u32
-            * self.blue.unwrap_or(0) as u32
+    /// Panics on overflow, see [`ColorSet::checked_add`]
+    fn add(self, other: &ColorSet) -> ColorSet {
+        self.checked_add(other).expect("color count overflow")
+    }
+}
+
+impl Sub for &ColorSet {
+    type Output = Result<ColorSet, ColorUnderflow>;
+
+    fn sub(self, other: &ColorSet) -> Self::Output {
+        self.checked_sub(other)
     }
 }
 
 /// Parses a color count record into a color and count
 /// The record should be in the format: <count> <color>
 /// For example: 1 red
-fn parse_color_count(record: &str) -> Result<(&str, ColorCount), ColorParserError> {
-    let mut parts = record.trim().split(" ");
-    let count_str = parts.next();
-    let color_str = parts.next();
-
-    // Extract the count and color parts
-    if let (Some(count), Some(color)) = (count_str, color_str) {
-        // Parse the count
-        let count = count.parse::<ColorCount>().or_else(|_| {
-            Err(ColorParserError::InvalidCount(
-                count_str
-                    .unwrap_or_else(|| "Value not provided")
-                    .trim()
-                    .to_string(),
-            ))
-        })?;
-        Ok((color, count))
-    } else {
-        Err(ColorParserError::InvalidColorCount(
-            record.trim().to_string(),
-        ))
-    }
+fn parse_color_count(record: Segment) -> Result<(Segment, ColorCount), SyntaxError> {
+    let (count, color) = record.pair("`<count> <color>`")?;
+    Ok((color, count.parse("a count")?))
 }
 
 #[cfg(test)]
@@ -139,52 +358,137 @@ mod test {
     #[test]
     fn parses_a_valid_colors_string() {
         let colors_str = "1 red, 2 green, 3 blue";
-        let color_count = ColorSet::parse(colors_str).unwrap();
+        let color_count = ColorSet::parse(colors_str, &mut Palette::rgb()).unwrap();
         assert_eq!(color_count, ColorSet::new(Some(1), Some(2), Some(3),));
     }
 
+    #[test]
+    fn parses_colors_of_other_palettes() {
+        let mut palette = Palette::declared(["red", "yellow"]);
+        let colors = ColorSet::parse("3 yellow, 1 red", &mut palette).unwrap();
+        assert_eq!(colors.get(Color::new("yellow")), Some(3));
+        assert_eq!(colors.power(), 3);
+        assert!(ColorSet::parse("1 blue", &mut palette).is_err());
+
+        let mut palette = Palette::inferred();
+        let colors = ColorSet::parse("2 cyan, 4 magenta, 5 cyan", &mut palette).unwrap();
+        assert_eq!(
+            colors.colors().collect::<Vec<_>>(),
+            [(Color::new("cyan"), 5), (Color::new("magenta"), 4)]
+        );
+        assert_eq!(
+            palette.colors().map(|c| c.name()).collect::<Vec<_>>(),
+            ["cyan", "magenta"]
+        );
+        assert!(colors.contains_all(&palette));
+        assert!(!colors.contains_all(&Palette::rgb()));
+    }
+
+    #[test]
+    fn compares_any_colors() {
+        let cyan = Color::new("cyan");
+        let bag = ColorSet::from_iter([(cyan, 5), (Color::RED, 3)]);
+        assert!(bag.covers(&ColorSet::from_iter([(cyan, 4)])));
+        assert!(bag.covers(&ColorSet::from_iter([(cyan, 5), (Color::RED, 3)])));
+        assert!(!bag.covers(&ColorSet::from_iter([(cyan, 6)])));
+        assert!(bag.covers(&ColorSet::from_iter([(Color::BLUE, 0)])));
+        assert!(!bag.covers(&ColorSet::from_iter([(Color::BLUE, 1)])));
+        assert!(bag.covers(&ColorSet::default()));
+        assert_eq!(ColorSet::default().power(), 0);
+    }
+
+    #[test]
+    fn orders_sets_by_dominance() {
+        let small = ColorSet::new(Some(1), Some(2), None);
+        let large = ColorSet::new(Some(1), Some(3), Some(0));
+        let other = ColorSet::new(Some(2), Some(1), None);
+        assert!(small < large);
+        assert!(large >= small);
+        assert_eq!(small.partial_cmp(&small.clone()), Some(Ordering::Equal));
+        assert_eq!(small.partial_cmp(&other), None);
+        assert!(!small.covers(&other) && !other.covers(&small));
+        assert_eq!(small, ColorSet::new(Some(1), Some(2), Some(0)));
+    }
+
+    #[test]
+    fn joins_and_meets_sets() {
+        let a = ColorSet::new(Some(1), Some(5), None);
+        let b = ColorSet::new(Some(4), Some(2), Some(3));
+        assert_eq!(a.join(&b), ColorSet::new(Some(4), Some(5), Some(3)));
+        assert_eq!(a.meet(&b), ColorSet::new(Some(1), Some(2), None));
+        assert!(a.meet(&b) <= a && a <= a.join(&b));
+    }
+
+    #[test]
+    fn adds_and_subtracts_sets() {
+        let a = ColorSet::new(Some(1), Some(5), None);
+        let b = ColorSet::new(Some(4), Some(2), Some(3));
+        let sum = &a + &b;
+        assert_eq!(sum, ColorSet::new(Some(5), Some(7), Some(3)));
+        assert_eq!(&sum - &b, Ok(ColorSet::new(Some(1), Some(5), Some(0))));
+        assert_eq!(
+            &a - &b,
+            Err(ColorUnderflow {
+                color: Color::BLUE,
+                available: 0,
+                taken: 3
+            })
+        );
+        let full = ColorSet::new(Some(ColorCount::MAX), None, None);
+        assert_eq!(full.checked_add(&a), None);
+    }
+
     #[test]
     fn fails_on_invalid_colors_string() {
         let colors_str = "1 red, 2 green, 3 yellow";
-        let result = ColorSet::parse(colors_str);
+        let result = ColorSet::parse(colors_str, &mut Palette::rgb());
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
-            ColorParserError::InvalidColor("yellow".to_string())
+            ColorParserError::InvalidColor("yellow".to_string(), Span::new(18, 24))
         );
 
         let colors_str = "1 red, 2 green, 3";
-        let result = ColorSet::parse(colors_str);
+        let result = ColorSet::parse(colors_str, &mut Palette::rgb());
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
-            ColorParserError::InvalidColorCount("3".to_string())
+            ColorParserError::Syntax(SyntaxError {
+                expected: "`<count> <color>`".to_string(),
+                found: "3".to_string(),
+                span: Span::new(16, 17)
+            })
         );
     }
 
     #[test]
     fn test_parse_color_count() {
-        let record = "1 red";
+        let record = Segment::new("1 red");
         let (color, count) = parse_color_count(record).unwrap();
-        assert_eq!(color, "red");
+        assert_eq!(color.as_str(), "red");
         assert_eq!(count, 1);
 
-        let record = "2 green";
+        let record = Segment::new("2 green");
         let (color, count) = parse_color_count(record).unwrap();
-        assert_eq!(color, "green");
+        assert_eq!(color.as_str(), "green");
         assert_eq!(count, 2);
 
-        let record = "3 blue";
+        let record = Segment::new(" 3  blue");
         let (color, count) = parse_color_count(record).unwrap();
-        assert_eq!(color, "blue");
+        assert_eq!(color.as_str(), "blue");
+        assert_eq!(color.span(), Span::new(4, 8));
         assert_eq!(count, 3);
 
-        let record = "five red";
+        let record = Segment::new("five red");
         let result = parse_color_count(record);
         assert!(result.is_err());
         assert_eq!(
             result.unwrap_err(),
-            ColorParserError::InvalidCount("five".to_string())
+            SyntaxError {
+                expected: "a count".to_string(),
+                found: "five".to_string(),
+                span: Span::new(0, 4)
+            }
         );
     }
 }
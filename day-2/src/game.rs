@@ -1,7 +1,14 @@
-use std::vec;
+use std::{collections::BTreeMap, fmt};
+
+use lib::{
+    diagnostic::Span,
+    error::{Error, ParseError},
+    parse::{Segment, SyntaxError},
+};
+use serde::Serialize;
 use thiserror::Error;
 
-use crate::color::{Color, ColorSet};
+use crate::color::{Color, ColorCount, ColorParserError, ColorSet, Palette};
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Game {
@@ -14,78 +21,163 @@ impl Game {
         Self { id, rounds }
     }
 
-    /// Parses a game record into a game
+    /// Parses a game record into a game, with the colors of the palette
     /// Valid game records are in the format: "Game <id>: <color count>, <color count>, <color count>[; <more color counts>...]"
-    pub fn parse(game_str: &str) -> Result<Self, GameParserError> {
-        let mut parts = game_str.trim().split(":");
-        let id_str = parts
-            .next()
-            .ok_or_else(|| GameParserError::InvalidGameId(game_str.to_string()))?;
-        let rounds_str = parts
-            .next()
-            .ok_or_else(|| GameParserError::InvalidGameRecord(game_str.to_string()))?;
-
-        // Extract the id and color parts
-        let id = id_str
-            .trim()
-            .strip_prefix("Game ")
-            .ok_or_else(|| GameParserError::InvalidGameId(id_str.to_string()))?
-            .parse::<u32>()
-            .or_else(|_| Err(GameParserError::InvalidGameId(id_str.to_string())))?;
-
-        let color_entries = rounds_str.split(";");
-
-        let mut colors = Vec::new();
-        for entry in color_entries {
-            let color_counts = ColorSet::parse(entry)
-                .or_else(|e| Err(GameParserError::InvalidColor(e.to_string())))?;
-            colors.push(color_counts);
+    pub fn parse(game_str: &str, palette: &mut Palette) -> Result<Self, GameParserError> {
+        let (id, rounds) = Segment::new(game_str).labelled("Game", ':')?;
+        let id = id.parse("a game id")?;
+        let rounds = rounds.list(';', |round| ColorSet::parse_segment(round, palette))?;
+        Ok(Game::new(id, rounds))
+    }
+
+    /// Rounds of the game, in the order they were played
+    pub fn rounds(&self) -> impl ExactSizeIterator<Item = &ColorSet> + '_ {
+        self.rounds.iter()
+    }
+
+    /// Number of rounds played
+    pub fn round_count(&self) -> usize {
+        self.rounds.len()
+    }
+
+    /// Cubes of the color drawn over every round
+    pub fn total(&self, color: Color) -> u32 {
+        self.counts(color).map(u32::from).sum()
+    }
+
+    /// Cubes of each color drawn over every round
+    pub fn totals(&self) -> BTreeMap<Color, u32> {
+        let mut totals = BTreeMap::new();
+        for (color, count) in self.rounds.iter().flat_map(ColorSet::colors) {
+            *totals.entry(color).or_default() += u32::from(count);
         }
+        totals
+    }
 
-        Ok(Game::new(id, colors))
+    /// Most cubes of the color drawn in a round, None when there are no rounds
+    pub fn max(&self, color: Color) -> Option<ColorCount> {
+        self.counts(color).max()
+    }
+
+    /// Fewest cubes of the color drawn in a round, 0 when a round does not
+    /// have it. None when there are no rounds.
+    pub fn min(&self, color: Color) -> Option<ColorCount> {
+        self.counts(color).min()
+    }
+
+    /// 1-based numbers of the rounds without the color
+    pub fn rounds_without(&self, color: Color) -> impl Iterator<Item = usize> + '_ {
+        self.rounds
+            .iter()
+            .enumerate()
+            .filter(move |(_, round)| round.get(color).is_none())
+            .map(|(index, _)| index + 1)
+    }
+
+    /// Count of the color in each round, 0 in the rounds without it
+    fn counts(&self, color: Color) -> impl Iterator<Item = ColorCount> + '_ {
+        self.rounds
+            .iter()
+            .map(move |round| round.get(color).unwrap_or(0))
     }
 
     /// Determines if a game is valid
     /// Validation criteria:
-    /// - All rounds must be valid
+    /// - The available cubes must cover every round
     pub fn is_valid(&self, available: &ColorSet) -> bool {
-        self.rounds.iter().all(|round| available.gt(round))
+        self.rounds.iter().all(|round| available.covers(round))
     }
 
-    /// Finds the minimum amount of colors required to pass a game
-    /// A color match is a color count that is less than or equal to the available count of that color
-    /// The matches are searched for in the RGB order on each game round
-    pub fn min_color_match(&self) -> Option<ColorSet> {
-        let mut result = ColorSet::default();
-        let colors = vec![Color::Red, Color::Green, Color::Blue];
-
-        for round in self.rounds.iter() {
-            for color in colors.iter() {
-                if round.gt_color(&result, color) {
-                    result.set(color, round.get(color));
+    /// Colors of each round drawn more times than available, empty when the
+    /// game is valid. Drawing none of a color is never a violation.
+    pub fn violations(&self, available: &ColorSet) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for (index, round) in self.rounds.iter().enumerate() {
+            for (color, drawn) in round.colors() {
+                let in_bag = available.get(color);
+                if drawn > in_bag.unwrap_or(0) {
+                    violations.push(Violation {
+                        round: index + 1,
+                        color,
+                        drawn,
+                        available: in_bag,
+                        margin: drawn - in_bag.unwrap_or(0),
+                    });
                 }
             }
         }
-        if result.all_some() {
-            return Some(result);
+        violations
+    }
+
+    /// Finds the minimum amount of colors required to pass a game
+    /// A color match is a color count that is less than or equal to the available count of that color
+    /// Colors the game never draws are left out, as no cube of them is needed
+    pub fn min_color_match(&self) -> ColorSet {
+        self.rounds
+            .iter()
+            .fold(ColorSet::default(), |result, round| result.join(round))
+    }
+}
+
+/// Color of a round drawn more times than there are cubes of it in the bag
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Violation {
+    /// 1-based round number in the game
+    pub round: usize,
+    pub color: Color,
+    pub drawn: ColorCount,
+    /// None when the bag has no cubes of the color at all
+    pub available: Option<ColorCount>,
+    /// Cubes drawn beyond the available ones
+    pub margin: ColorCount,
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "round {}: {} {} drawn, ",
+            self.round, self.drawn, self.color
+        )?;
+        match self.available {
+            Some(available) => write!(f, "{} available", available)?,
+            None => write!(f, "none in the bag")?,
         }
-        None
+        write!(f, " ({} too many)", self.margin)
     }
 }
 
+/// Games of an input and the palette of their colors
+#[derive(Debug, PartialEq, Eq)]
+pub struct Games {
+    /// Colors of the games, including the ones learnt while parsing them
+    pub palette: Palette,
+    pub games: Vec<Game>,
+}
+
+/// Parsing errors, with the span of the invalid part of the game record
 #[derive(Error, Debug, PartialEq, Eq)]
 pub enum GameParserError {
-    #[error("Invalid game record: {0}")]
-    InvalidGameRecord(String),
+    #[error("Invalid color set")]
+    InvalidColorSet(#[from] ColorParserError),
 
-    #[error("Invalid game id: {0}")]
-    InvalidGameId(String),
+    #[error(transparent)]
+    Syntax(#[from] SyntaxError),
+}
 
-    #[error("Invalid color: {0}")]
-    InvalidColor(String),
+impl ParseError for GameParserError {
+    fn span(&self) -> Option<Span> {
+        match self {
+            GameParserError::InvalidColorSet(error) => error.span(),
+            GameParserError::Syntax(error) => error.span(),
+        }
+    }
+}
 
-    #[error("Invalid count: {0}")]
-    InvalidCount(String),
+impl From<GameParserError> for Error {
+    fn from(error: GameParserError) -> Self {
+        Error::parse(error)
+    }
 }
 
 #[cfg(test)]
@@ -95,17 +187,46 @@ mod test {
     #[test]
     fn parses_a_valid_game_string() {
         let game_str = "Game 1: 1 red, 3 blue, 2 green";
-        let game = Game::parse(game_str).unwrap();
+        let game = Game::parse(game_str, &mut Palette::rgb()).unwrap();
         assert_eq!(
             game,
             Game::new(1, vec![ColorSet::new(Some(1), Some(2), Some(3),)])
         );
     }
 
+    #[test]
+    fn keeps_the_color_error_as_source() {
+        let result = Game::parse("Game 1: 1 red, 2 yellow", &mut Palette::rgb());
+        assert_eq!(
+            result,
+            Err(GameParserError::InvalidColorSet(
+                ColorParserError::InvalidColor("yellow".to_string(), Span::new(17, 23))
+            ))
+        );
+    }
+
+    #[test]
+    fn reports_the_span_of_invalid_parts() {
+        let game_str = "Game 12: 3 blue, x red";
+        let error = Game::parse(game_str, &mut Palette::rgb()).unwrap_err();
+        assert_eq!(error.span(), Some(Span::new(17, 18)));
+
+        let game_str = "  Gme 12: 3 blue";
+        let error = Game::parse(game_str, &mut Palette::rgb()).unwrap_err();
+        assert_eq!(
+            error,
+            GameParserError::Syntax(SyntaxError {
+                expected: "`Game <value>`".to_string(),
+                found: "Gme 12".to_string(),
+                span: Span::new(2, 8)
+            })
+        );
+    }
+
     #[test]
     fn parses_a_valid_game_string_with_multiple_rounds() {
         let game_str = "Game 1: 1 red, 2 green, 3 blue; 4 red, 5 green, 6 blue";
-        let game = Game::parse(game_str).unwrap();
+        let game = Game::parse(game_str, &mut Palette::rgb()).unwrap();
         assert_eq!(
             game,
             Game::new(
@@ -118,13 +239,91 @@ mod test {
         );
     }
 
+    #[test]
+    fn accepts_rounds_drawing_every_available_cube() {
+        let available = ColorSet::new(Some(12), Some(13), Some(14));
+        let game = Game::parse("Game 1: 12 red; 13 green, 14 blue", &mut Palette::rgb()).unwrap();
+        assert!(game.is_valid(&available));
+
+        let game = Game::parse("Game 2: 12 red; 14 green", &mut Palette::rgb()).unwrap();
+        assert!(!game.is_valid(&available));
+
+        let game = Game::parse("Game 3: 1 cyan", &mut Palette::inferred()).unwrap();
+        assert!(!game.is_valid(&available));
+
+        let game = Game::parse("Game 4: 0 cyan, 12 red", &mut Palette::inferred()).unwrap();
+        assert!(game.is_valid(&available));
+    }
+
+    #[test]
+    fn lists_the_violations_of_each_round() {
+        let available = ColorSet::new(Some(12), Some(13), Some(14));
+        let game_str = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 15 blue, 13 red";
+        let game = Game::parse(game_str, &mut Palette::rgb()).unwrap();
+        let violation = |round, color, drawn, available| Violation {
+            round,
+            color,
+            drawn,
+            available: Some(available),
+            margin: drawn - available,
+        };
+        assert_eq!(
+            game.violations(&available),
+            [
+                violation(1, Color::RED, 20, 12),
+                violation(3, Color::BLUE, 15, 14),
+                violation(3, Color::RED, 13, 12),
+            ]
+        );
+        assert_eq!(
+            game.violations(&available)[0].to_string(),
+            "round 1: 20 red drawn, 12 available (8 too many)"
+        );
+
+        let game = Game::parse("Game 4: 0 cyan; 2 cyan", &mut Palette::inferred()).unwrap();
+        let violations = game.violations(&available);
+        assert_eq!(violations.len(), 1);
+        assert_eq!(violations[0].available, None);
+        assert_eq!(
+            violations[0].to_string(),
+            "round 2: 2 cyan drawn, none in the bag (2 too many)"
+        );
+        assert_eq!(violations.is_empty(), game.is_valid(&available));
+    }
+
+    #[test]
+    fn reads_the_rounds() {
+        let game_str = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
+        let game = Game::parse(game_str, &mut Palette::rgb()).unwrap();
+        assert_eq!(game.round_count(), 3);
+        assert_eq!(
+            game.rounds().nth(1),
+            Some(&ColorSet::new(Some(4), Some(13), Some(5)))
+        );
+        assert_eq!(game.total(Color::GREEN), 26);
+        assert_eq!(
+            game.totals().into_iter().collect::<Vec<_>>(),
+            [(Color::BLUE, 11), (Color::GREEN, 26), (Color::RED, 25)]
+        );
+        assert_eq!(game.max(Color::RED), Some(20));
+        assert_eq!(game.min(Color::BLUE), Some(0));
+        assert_eq!(game.min(Color::GREEN), Some(5));
+        assert_eq!(game.rounds_without(Color::BLUE).collect::<Vec<_>>(), [3]);
+
+        let cyan = Color::new("cyan");
+        assert_eq!(game.total(cyan), 0);
+        assert_eq!(game.max(cyan), Some(0));
+        assert_eq!(Game::new(4, Vec::new()).max(cyan), None);
+        assert_eq!(game.rounds_without(cyan).count(), 3);
+    }
+
     /// Example from the Advent of Code website
     #[test]
     fn gets_minimum_game_1() {
         let game_1_str = "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green";
-        let game_1 = Game::parse(game_1_str).unwrap();
+        let game_1 = Game::parse(game_1_str, &mut Palette::rgb()).unwrap();
 
-        let result = game_1.min_color_match().unwrap();
+        let result = game_1.min_color_match();
         assert_eq!(result, ColorSet::new(Some(4), Some(2), Some(6)));
     }
 
@@ -132,9 +331,9 @@ mod test {
     #[test]
     fn gets_minimum_game_3() {
         let game_3_str = "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red";
-        let game_3 = Game::parse(game_3_str).unwrap();
+        let game_3 = Game::parse(game_3_str, &mut Palette::rgb()).unwrap();
 
-        let result = game_3.min_color_match().unwrap();
+        let result = game_3.min_color_match();
         assert_eq!(result, ColorSet::new(Some(20), Some(13), Some(6)));
     }
 
@@ -142,9 +341,21 @@ mod test {
     #[test]
     fn gets_minimum_game_5() {
         let game_5_str = "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
-        let game_5 = Game::parse(game_5_str).unwrap();
+        let game_5 = Game::parse(game_5_str, &mut Palette::rgb()).unwrap();
 
-        let result = game_5.min_color_match().unwrap();
+        let result = game_5.min_color_match();
         assert_eq!(result, ColorSet::new(Some(6), Some(3), Some(2)));
     }
+
+    #[test]
+    fn gets_minimum_game_of_any_palette() {
+        let mut palette = Palette::inferred();
+        let game_str = "Game 7: 2 cyan, 1 red; 3 cyan; 4 yellow";
+        let game = Game::parse(game_str, &mut palette).unwrap();
+        let result = game.min_color_match();
+        assert_eq!(result.power(), 3 * 4);
+
+        let black = palette.resolve("black").unwrap();
+        assert_eq!(game.min_color_match().get(black), None);
+    }
 }
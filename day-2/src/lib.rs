@@ -1,2 +1,4 @@
 pub mod color;
 pub mod game;
+pub mod report;
+pub mod solver;
@@ -0,0 +1,129 @@
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::{
+    color::ColorSet,
+    game::{Games, Violation},
+};
+
+/// Why a game is impossible with the cubes of the bag
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ImpossibleGame {
+    pub id: u32,
+    /// Never empty
+    pub violations: Vec<Violation>,
+}
+
+/// Games excluded from the sum of the ids of part 1, and the reasons why
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ValidationReport {
+    /// Ids of the possible games, in input order
+    pub possible: Vec<u32>,
+    /// Impossible games, in input order
+    pub impossible: Vec<ImpossibleGame>,
+    /// Sum of the ids of the possible games, the answer to part 1
+    pub id_sum: u32,
+}
+
+impl ValidationReport {
+    /// Checks every game against the cubes available in the bag
+    pub fn new(games: &Games, available: &ColorSet) -> Self {
+        let mut report = Self {
+            possible: Vec::new(),
+            impossible: Vec::new(),
+            id_sum: 0,
+        };
+        for game in games.games.iter() {
+            let violations = game.violations(available);
+            if violations.is_empty() {
+                report.possible.push(game.id);
+                report.id_sum += game.id;
+            } else {
+                report.impossible.push(ImpossibleGame {
+                    id: game.id,
+                    violations,
+                });
+            }
+        }
+        report
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for game in self.impossible.iter() {
+            writeln!(f, "Game {} is impossible", game.id)?;
+            for violation in game.violations.iter() {
+                writeln!(f, "  {}", violation)?;
+            }
+        }
+        write!(
+            f,
+            "{} possible and {} impossible games, sum of the possible ids: {}",
+            self.possible.len(),
+            self.impossible.len(),
+            self.id_sum
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lib::solver::Solution;
+
+    use crate::{color::Color, solver::Day2};
+
+    use super::*;
+
+    const EXAMPLE: &str = "\
+Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green
+Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue
+Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red
+Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red
+Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green";
+
+    fn report() -> ValidationReport {
+        let day = Day2::default();
+        let games = day.parse(&mut EXAMPLE.lines()).unwrap();
+        ValidationReport::new(&games, &day.available_colors)
+    }
+
+    #[test]
+    fn explains_the_impossible_games() {
+        let report = report();
+        assert_eq!(report.possible, [1, 2, 5]);
+        assert_eq!(report.id_sum, 8);
+        assert_eq!(
+            report.impossible[0],
+            ImpossibleGame {
+                id: 3,
+                violations: vec![Violation {
+                    round: 1,
+                    color: Color::RED,
+                    drawn: 20,
+                    available: Some(12),
+                    margin: 8,
+                }]
+            }
+        );
+        assert_eq!(
+            report.to_string(),
+            "Game 3 is impossible\n  \
+             round 1: 20 red drawn, 12 available (8 too many)\n\
+             Game 4 is impossible\n  \
+             round 3: 15 blue drawn, 14 available (1 too many)\n  \
+             round 3: 14 red drawn, 12 available (2 too many)\n\
+             3 possible and 2 impossible games, sum of the possible ids: 8"
+        );
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let json = serde_json::to_value(report()).unwrap();
+        assert_eq!(json["id_sum"], 8);
+        assert_eq!(json["impossible"][1]["id"], 4);
+        assert_eq!(json["impossible"][1]["violations"][0]["color"], "blue");
+        assert_eq!(json["impossible"][1]["violations"][0]["margin"], 1);
+    }
+}
@@ -0,0 +1,123 @@
+use lib::{
+    error::{Error, Result, ResultExt},
+    input::{try_for_each_line, LineSource},
+    solver::Solution,
+};
+
+use crate::{
+    color::{Color, ColorCount, ColorSet, Palette},
+    game::{Game, Games},
+    report::ValidationReport,
+};
+
+/// Cubes in the bag for part 1
+pub const AVAILABLE_COLORS: [(Color, ColorCount); 3] =
+    [(Color::RED, 12), (Color::GREEN, 13), (Color::BLUE, 14)];
+
+/// Cube conundrum
+pub struct Day2 {
+    /// Cubes in the bag, used to check which games are possible
+    pub available_colors: ColorSet,
+    /// Colors of the cubes, red, green and blue by default
+    pub palette: Palette,
+}
+
+impl Default for Day2 {
+    fn default() -> Self {
+        Self {
+            available_colors: ColorSet::from_iter(AVAILABLE_COLORS),
+            palette: Palette::rgb(),
+        }
+    }
+}
+
+impl Day2 {
+    /// Explains which games are impossible with the available colors, and why
+    pub fn validate(&self, games: &Games) -> ValidationReport {
+        ValidationReport::new(games, &self.available_colors)
+    }
+}
+
+impl Solution for Day2 {
+    type Input = Games;
+    type PartOne = u32;
+    type PartTwo = u64;
+
+    const DAY: u8 = 2;
+    const INPUTS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/inputs");
+
+    fn parse(&self, lines: &mut dyn LineSource) -> Result<Self::Input> {
+        let mut palette = self.palette.clone();
+        let mut games = Vec::new();
+        try_for_each_line(lines, |line_number, line| {
+            games.push(Game::parse(line, &mut palette).at_line(line_number, line)?);
+            Ok::<(), Error>(())
+        })?;
+        Ok(Games { palette, games })
+    }
+
+    /// Sum of the ids of the games that are possible with the available colors
+    fn part_one(&self, games: &Self::Input) -> Result<Self::PartOne> {
+        Ok(games
+            .games
+            .iter()
+            .filter(|game| game.is_valid(&self.available_colors))
+            .map(|game| game.id)
+            .sum())
+    }
+
+    /// Sum of the power of the minimum set of cubes of each game
+    fn part_two(&self, games: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(games
+            .games
+            .iter()
+            .map(|game| game.min_color_match().power())
+            .sum())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn website_example_input() -> Vec<String> {
+        vec![
+            "Game 1: 3 blue, 4 red; 1 red, 2 green, 6 blue; 2 green".to_string(),
+            "Game 2: 1 blue, 2 green; 3 green, 4 blue, 1 red; 1 green, 1 blue".to_string(),
+            "Game 3: 8 green, 6 blue, 20 red; 5 blue, 4 red, 13 green; 5 green, 1 red".to_string(),
+            "Game 4: 1 green, 3 red, 6 blue; 3 green, 6 red; 3 green, 15 blue, 14 red".to_string(),
+            "Game 5: 6 red, 1 blue, 3 green; 2 blue, 1 red, 2 green".to_string(),
+        ]
+    }
+
+    /// Example from the Advent of Code website
+    /// https://adventofcode.com/2023/day/2
+    #[test]
+    fn website_example_part_1() {
+        let day = Day2::default();
+        let games = day.parse(&mut website_example_input().iter()).unwrap();
+        assert_eq!(day.part_one(&games).unwrap(), 8);
+    }
+
+    /// Example from the Advent of Code website
+    /// https://adventofcode.com/2023/day/2#part2
+    #[test]
+    fn website_example_part_2() {
+        let day = Day2::default();
+        let games = day.parse(&mut website_example_input().iter()).unwrap();
+        assert_eq!(day.part_two(&games).unwrap(), 2286);
+    }
+
+    #[test]
+    fn solves_part_2_when_a_color_is_in_a_single_game() {
+        let day = Day2 {
+            palette: Palette::inferred(),
+            ..Day2::default()
+        };
+        let mut input = website_example_input();
+        input.push("Game 6: 2 red, 1 yellow; 3 blue, 2 green, 4 yellow".to_string());
+        let games = day.parse(&mut input.iter()).unwrap();
+        assert_eq!(games.palette.colors().count(), 4);
+        assert_eq!(day.part_two(&games).unwrap(), 2286 + 2 * 2 * 3 * 4);
+    }
+}
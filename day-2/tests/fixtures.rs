@@ -0,0 +1,8 @@
+use std::process::ExitCode;
+
+use day_2::solver::Day2;
+use lib::fixtures::run_tests;
+
+fn main() -> ExitCode {
+    run_tests(Day2::default())
+}
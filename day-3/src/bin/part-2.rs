@@ -1,35 +1,8 @@
 use std::process::ExitCode;
 
-use day_3::numbers::Schematic;
-use lib::input::read_file_lines;
+use day_3::solver::Day3;
+use lib::solver::{run_part, Part};
 
 fn main() -> ExitCode {
-    // let path = "./inputs/test.txt";
-    let path = "./inputs/part-1.txt";
-
-    let lines = match read_file_lines(path) {
-        Ok(lines) => lines,
-        Err(e) => {
-            eprintln!("Error reading the input file: {}", e);
-            return ExitCode::FAILURE;
-        }
-    };
-
-    match part_2_logic(&lines) {
-        Ok(result) => {
-            println!("Gear ratios sum: {}", result);
-            return ExitCode::SUCCESS;
-        }
-        Err(e) => {
-            eprintln!("error: {}", e);
-            return ExitCode::FAILURE;
-        }
-    };
-}
-
-fn part_2_logic(game_lines: &Vec<String>) -> Result<u32, String> {
-    let schematic = Schematic::parse(game_lines)
-        .or_else(|err| Err(format!("Error parsing the schematic: {}", err.to_string())))?;
-
-    Ok(schematic.get_gear_ratios_sum())
+    run_part(&Day3, Part::Two)
 }
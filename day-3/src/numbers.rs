@@ -1,18 +1,56 @@
-use std::collections::HashMap;
+use std::collections::BTreeMap;
 
+use lib::{
+    diagnostic::Span,
+    error::{Error, ParseError},
+    grid::{Grid, GridError, Point},
+};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum SchematicParserError {
-    #[error("Invalid number {0}")]
-    InvalidNumber(String),
+    /// Number on the given 1-based line that does not fit in a `u32`
+    #[error("Invalid number {number}")]
+    InvalidNumber {
+        number: String,
+        line: usize,
+        span: Span,
+    },
+
+    #[error("Invalid schematic")]
+    InvalidGrid(#[from] GridError),
+}
+
+impl SchematicParserError {
+    /// 1-based line of the schematic where the error was found
+    pub fn line(&self) -> usize {
+        match self {
+            SchematicParserError::InvalidNumber { line, .. } => *line,
+            SchematicParserError::InvalidGrid(GridError::Ragged { row, .. }) => *row,
+            SchematicParserError::InvalidGrid(_) => 1,
+        }
+    }
+}
+
+impl ParseError for SchematicParserError {
+    fn span(&self) -> Option<Span> {
+        match self {
+            SchematicParserError::InvalidNumber { span, .. } => Some(*span),
+            SchematicParserError::InvalidGrid(error) => error.span(),
+        }
+    }
+}
+
+impl From<SchematicParserError> for Error {
+    fn from(error: SchematicParserError) -> Self {
+        Error::parse(error)
+    }
 }
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct Symbol {
     pub character: char,
-    pub pos_x: usize,
-    pub pos_y: usize,
+    pub position: Point,
 }
 
 pub struct Num {
@@ -34,36 +72,34 @@ pub struct Schematic {
 }
 
 impl Schematic {
-    pub fn parse(lines: &Vec<String>) -> Result<Self, SchematicParserError> {
-        let mut current_num = String::new();
+    pub fn parse(lines: &[String]) -> Result<Self, SchematicParserError> {
+        let grid = Grid::parse(lines.iter().map(String::as_str))?;
         let mut parts = Vec::new();
-        let mut adjacent_symbol = None;
-
-        for (y, line) in lines.iter().enumerate() {
-            for (x, character) in line.chars().enumerate() {
-                if character.is_digit(10) {
-                    current_num.push(character);
-                    if adjacent_symbol.is_none() {
-                        adjacent_symbol = get_adjacent_symbol(x, y, lines);
-                    }
-                } else {
-                    if !current_num.is_empty() {
-                        let num = current_num.parse::<u32>().map_err(|_| {
-                            SchematicParserError::InvalidNumber(current_num.to_string())
-                        })?;
-                        parts.push(Num::new(num, adjacent_symbol));
-                        current_num.clear();
-                        adjacent_symbol = None;
-                    }
+
+        for (y, row) in grid.rows().enumerate() {
+            let mut x = 0;
+            while x < row.len() {
+                if !row[x].is_ascii_digit() {
+                    x += 1;
+                    continue;
                 }
-            }
-            if !current_num.is_empty() {
-                let num = current_num
-                    .parse::<u32>()
-                    .map_err(|_| SchematicParserError::InvalidNumber(current_num.to_string()))?;
-                parts.push(Num::new(num, adjacent_symbol));
-                current_num.clear();
-                adjacent_symbol = None;
+                let start = x;
+                while x < row.len() && row[x].is_ascii_digit() {
+                    x += 1;
+                }
+
+                let number = row[start..x].iter().collect::<String>();
+                let value =
+                    number
+                        .parse::<u32>()
+                        .map_err(|_| SchematicParserError::InvalidNumber {
+                            span: Span::new(byte_offset(row, start), byte_offset(row, x)),
+                            number,
+                            line: y + 1,
+                        })?;
+                let adjacent_symbol =
+                    (start..x).find_map(|x| get_adjacent_symbol(&grid, Point::new(x, y)));
+                parts.push(Num::new(value, adjacent_symbol));
             }
         }
 
@@ -99,11 +135,11 @@ impl Schematic {
     pub fn get_gear_pairs(&self) -> Vec<(&Num, &Num)> {
         let mut pairs = Vec::new();
         let gear_parts = self.get_gear_adjacent_parts();
-        let mut adjacent_gear_parts = HashMap::<(usize, usize), Vec<&&Num>>::new();
+        let mut adjacent_gear_parts = BTreeMap::<Point, Vec<&&Num>>::new();
 
         for part in gear_parts.iter() {
             if let Some(symbol) = &part.adjacent_symbol {
-                let gear_position = (symbol.pos_x, symbol.pos_y);
+                let gear_position = symbol.position;
                 match adjacent_gear_parts.get_mut(&gear_position) {
                     Some(parts) => parts.push(part),
                     None => {
@@ -130,52 +166,19 @@ impl Schematic {
     }
 }
 
-#[derive(PartialEq, Eq)]
-enum CheckPosition {
-    Prev,
-    Current,
-    Next,
-}
-
-fn get_adjacent_symbol(x: usize, y: usize, lines: &Vec<String>) -> Option<Symbol> {
-    let mut symbol = None;
-    let positions = vec![
-        CheckPosition::Prev,
-        CheckPosition::Current,
-        CheckPosition::Next,
-    ];
-
-    'outer: for i in positions.iter() {
-        for j in positions.iter() {
-            if *i == CheckPosition::Current && *j == CheckPosition::Current {
-                continue; // skip the center
-            }
-
-            if let (Some(x), Some(y)) = (get_check_position(x, i), get_check_position(y, j)) {
-                if let Some(line) = lines.get(y) {
-                    if let Some(character) = line.chars().nth(x) {
-                        if !character.is_digit(10) && character != '.' {
-                            symbol = Some(Symbol {
-                                character,
-                                pos_x: x,
-                                pos_y: y,
-                            });
-                            break 'outer;
-                        }
-                    }
-                }
-            }
-        }
-    }
-    symbol
+/// First symbol around a cell, anything but a digit or a `.`
+fn get_adjacent_symbol(grid: &Grid<char>, point: Point) -> Option<Symbol> {
+    grid.neighbors8(point)
+        .find(|(_, character)| !character.is_ascii_digit() && **character != '.')
+        .map(|(position, character)| Symbol {
+            character: *character,
+            position,
+        })
 }
 
-fn get_check_position(position: usize, check_position: &CheckPosition) -> Option<usize> {
-    match check_position {
-        CheckPosition::Prev => position.checked_sub(1),
-        CheckPosition::Current => Some(position),
-        CheckPosition::Next => position.checked_add(1),
-    }
+/// Byte offset of a cell in the line of its row
+fn byte_offset(row: &[char], x: usize) -> usize {
+    row[..x].iter().map(|character| character.len_utf8()).sum()
 }
 
 #[cfg(test)]
@@ -199,17 +202,28 @@ mod test {
 
     #[test]
     fn test_is_part() {
-        let lines = vec!["467..114..".to_string(), "...*......".to_string()];
-        assert_eq!(get_adjacent_symbol(0, 0, &lines), None);
+        let grid = Grid::parse(["467..114..", "...*......"]).unwrap();
+        assert_eq!(get_adjacent_symbol(&grid, Point::new(0, 0)), None);
         assert_eq!(
-            get_adjacent_symbol(2, 0, &lines),
+            get_adjacent_symbol(&grid, Point::new(2, 0)),
             Some(Symbol {
                 character: '*',
-                pos_x: 3,
-                pos_y: 1
+                position: Point::new(3, 1)
             })
         );
-        assert_eq!(get_adjacent_symbol(5, 0, &lines), None);
+        assert_eq!(get_adjacent_symbol(&grid, Point::new(5, 0)), None);
+    }
+
+    #[test]
+    fn fails_on_numbers_too_large() {
+        let lines = vec!["..1.........".to_string(), "*99999999999".to_string()];
+        let error = Schematic::parse(&lines).err().unwrap();
+        assert_eq!(error.line(), 2);
+        assert_eq!(error.span(), Some(Span::new(1, 12)));
+
+        let lines = vec!["99999999999.".to_string()];
+        let error = Schematic::parse(&lines).err().unwrap();
+        assert_eq!(error.span(), Some(Span::new(0, 11)));
     }
 
     #[test]
@@ -220,6 +234,14 @@ mod test {
         assert_eq!(result.get_non_part_numbers(), vec![114]);
     }
 
+    #[test]
+    fn fails_on_ragged_lines() {
+        let lines = vec!["467..".to_string(), "...*......".to_string()];
+        let error = Schematic::parse(&lines).err().unwrap();
+        assert_eq!(error.line(), 2);
+        assert_eq!(error.span(), Some(Span::new(0, 10)));
+    }
+
     /// Test from the website's problem description
     #[test]
     fn parses_full_schematic() {
@@ -0,0 +1,37 @@
+use lib::{
+    error::{Error, Result},
+    input::{collect_lines, LineSource},
+    solver::Solution,
+};
+
+use crate::numbers::Schematic;
+
+/// Gear ratios
+pub struct Day3;
+
+impl Solution for Day3 {
+    type Input = Schematic;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    const DAY: u8 = 3;
+    const INPUTS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/inputs");
+
+    fn parse(&self, lines: &mut dyn LineSource) -> Result<Self::Input> {
+        let lines = collect_lines(lines)?;
+        Schematic::parse(&lines).map_err(|e| {
+            let line_number = e.line();
+            Error::parse_at(line_number, &lines[line_number - 1], e)
+        })
+    }
+
+    /// Sum of the numbers adjacent to a symbol
+    fn part_one(&self, schematic: &Self::Input) -> Result<Self::PartOne> {
+        Ok(schematic.get_part_numbers().iter().sum())
+    }
+
+    /// Sum of the gear ratios
+    fn part_two(&self, schematic: &Self::Input) -> Result<Self::PartTwo> {
+        Ok(schematic.get_gear_ratios_sum())
+    }
+}
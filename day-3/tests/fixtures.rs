@@ -0,0 +1,8 @@
+use std::process::ExitCode;
+
+use day_3::solver::Day3;
+use lib::fixtures::run_tests;
+
+fn main() -> ExitCode {
+    run_tests(Day3)
+}
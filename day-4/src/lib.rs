@@ -1 +1,2 @@
 pub mod scratchcard;
+pub mod solver;
@@ -1,52 +1,50 @@
+use lib::{
+    diagnostic::Span,
+    error::{Error, ParseError},
+    integers::IntegerError,
+    parse::{Segment, SyntaxError},
+};
 use thiserror::Error;
 
-pub fn get_winning_numbers(card_data: &str) -> Result<Vec<u32>, ScratchCardParseError> {
-    let content = card_data.split(":").collect::<Vec<&str>>();
-    let card_numbers_data = content
-        .get(1)
-        .ok_or_else(|| ScratchCardParseError::InvalidCardData(card_data.to_string()))?
-        .trim();
-    let winning_numbers_data = card_numbers_data
-        .split("|")
-        .next()
-        .ok_or_else(|| ScratchCardParseError::InvalidCardData(card_data.to_string()))?;
-
-    let winning_numbers = winning_numbers_data
-        .trim()
-        .replace("  ", " ")
-        .split(" ")
-        .map(|num| {
-            num.parse::<u32>()
-                .map_err(|_| ScratchCardParseError::InvalidNumber(num.to_string()))
+#[derive(Debug, PartialEq, Eq)]
+pub struct Card {
+    pub winning_numbers: Vec<u32>,
+    pub card_numbers: Vec<u32>,
+}
+
+impl Card {
+    /// Parses a card record in the format: "Card <id>: <winning numbers> | <card numbers>"
+    pub fn parse(card_data: &str) -> Result<Self, ScratchCardParseError> {
+        let (winning_numbers, card_numbers) = get_numbers_data(card_data)?;
+        Ok(Self {
+            winning_numbers: winning_numbers.integers()?,
+            card_numbers: card_numbers.integers()?,
         })
-        .collect::<Result<Vec<u32>, ScratchCardParseError>>()?;
-    Ok(winning_numbers)
+    }
+
+    /// Numbers of the card that are also winning numbers
+    pub fn common_numbers(&self) -> Vec<u32> {
+        find_common_numbers(&self.winning_numbers, &self.card_numbers)
+    }
+}
+
+pub fn get_winning_numbers(card_data: &str) -> Result<Vec<u32>, ScratchCardParseError> {
+    let (winning_numbers, _) = get_numbers_data(card_data)?;
+    Ok(winning_numbers.integers()?)
 }
 
 pub fn get_card_numbers(card_data: &str) -> Result<Vec<u32>, ScratchCardParseError> {
-    let content = card_data.split(":").collect::<Vec<&str>>();
-    let card_numbers_data = content
-        .get(1)
-        .ok_or_else(|| ScratchCardParseError::InvalidCardData(card_data.to_string()))?
-        .trim()
-        .split("|")
-        .collect::<Vec<&str>>();
-    let card_numbers_data = card_numbers_data
-        .get(1)
-        .ok_or_else(|| ScratchCardParseError::InvalidCardData(card_data.to_string()))?;
-    let card_numbers = card_numbers_data
-        .trim()
-        .replace("  ", " ")
-        .split(" ")
-        .map(|num| {
-            num.parse::<u32>()
-                .map_err(|_| ScratchCardParseError::InvalidNumber(num.to_string()))
-        })
-        .collect::<Result<Vec<u32>, ScratchCardParseError>>()?;
-    Ok(card_numbers)
+    let (_, card_numbers) = get_numbers_data(card_data)?;
+    Ok(card_numbers.integers()?)
+}
+
+/// Winning numbers and card numbers parts of the card, after the id
+fn get_numbers_data(card_data: &str) -> Result<(Segment<'_>, Segment<'_>), ScratchCardParseError> {
+    let (_, numbers) = Segment::new(card_data).labelled("Card", ':')?;
+    Ok(numbers.split_once('|')?)
 }
 
-pub fn find_common_numbers(nums_1: &Vec<u32>, nums_2: &Vec<u32>) -> Vec<u32> {
+pub fn find_common_numbers(nums_1: &[u32], nums_2: &[u32]) -> Vec<u32> {
     nums_1
         .iter()
         .filter(|num| nums_2.contains(num))
@@ -54,7 +52,7 @@ pub fn find_common_numbers(nums_1: &Vec<u32>, nums_2: &Vec<u32>) -> Vec<u32> {
         .collect()
 }
 
-pub fn array_score(nums: &Vec<u32>) -> u32 {
+pub fn array_score(nums: &[u32]) -> u32 {
     let len = nums.len();
     if len > 0 {
         2u32.pow((len as u32) - 1)
@@ -63,13 +61,29 @@ pub fn array_score(nums: &Vec<u32>) -> u32 {
     }
 }
 
-#[derive(Error, Debug)]
+/// Parsing errors, with the span of the invalid part of the card
+#[derive(Error, Debug, PartialEq, Eq)]
 pub enum ScratchCardParseError {
-    #[error("Invalid number {0}")]
-    InvalidNumber(String),
+    #[error(transparent)]
+    Syntax(#[from] SyntaxError),
 
-    #[error("Invalid card data {0}")]
-    InvalidCardData(String),
+    #[error(transparent)]
+    Number(#[from] IntegerError),
+}
+
+impl ParseError for ScratchCardParseError {
+    fn span(&self) -> Option<Span> {
+        match self {
+            ScratchCardParseError::Syntax(error) => error.span(),
+            ScratchCardParseError::Number(error) => error.span(),
+        }
+    }
+}
+
+impl From<ScratchCardParseError> for Error {
+    fn from(error: ScratchCardParseError) -> Self {
+        Error::parse(error)
+    }
 }
 
 #[cfg(test)]
@@ -90,6 +104,44 @@ mod tests {
         assert_eq!(card_numbers, vec![83, 86, 6, 31, 17, 9, 48, 53]);
     }
 
+    #[test]
+    fn test_invalid_numbers_span() {
+        let card_data = "Card 1: 41 48 | 83  8x 17";
+        let result = get_card_numbers(card_data);
+        assert_eq!(
+            result,
+            Err(ScratchCardParseError::Number(IntegerError::Syntax(
+                SyntaxError {
+                    expected: "an integer".to_string(),
+                    found: "8x".to_string(),
+                    span: Span::new(20, 22)
+                }
+            )))
+        );
+
+        let card_data = "Card 1: 41 | 4294967296";
+        let result = get_card_numbers(card_data);
+        assert_eq!(
+            result,
+            Err(ScratchCardParseError::Number(IntegerError::Overflow {
+                text: "4294967296".to_string(),
+                type_name: "u32",
+                span: Span::new(13, 23)
+            }))
+        );
+
+        let card_data = "Card 1 41 48 | 83 86";
+        let result = get_winning_numbers(card_data);
+        assert_eq!(
+            result,
+            Err(ScratchCardParseError::Syntax(SyntaxError {
+                expected: "`:`".to_string(),
+                found: card_data.to_string(),
+                span: Span::new(0, 20)
+            }))
+        );
+    }
+
     #[test]
     fn test_find_common_numbers() {
         let nums_1 = vec![83, 86, 6, 31, 17, 9, 48, 53];
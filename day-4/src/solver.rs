@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+
+use lib::{
+    error::{Error, Result, ResultExt},
+    input::{try_for_each_line, LineSource},
+    solver::Solution,
+};
+
+use crate::scratchcard::{array_score, Card};
+
+/// Scratchcards
+pub struct Day4;
+
+impl Solution for Day4 {
+    type Input = Vec<Card>;
+    type PartOne = u32;
+    type PartTwo = u32;
+
+    const DAY: u8 = 4;
+    const INPUTS: &'static str = concat!(env!("CARGO_MANIFEST_DIR"), "/inputs");
+
+    fn parse(&self, lines: &mut dyn LineSource) -> Result<Self::Input> {
+        let mut cards = Vec::new();
+        try_for_each_line(lines, |line_number, line| {
+            cards.push(Card::parse(line).at_line(line_number, line)?);
+            Ok::<(), Error>(())
+        })?;
+        Ok(cards)
+    }
+
+    /// Sum of the scores of every card
+    fn part_one(&self, cards: &Self::Input) -> Result<Self::PartOne> {
+        Ok(cards
+            .iter()
+            .map(|card| array_score(&card.common_numbers()))
+            .sum())
+    }
+
+    /// Total amount of cards, counting the won copies
+    fn part_two(&self, cards: &Self::Input) -> Result<Self::PartTwo> {
+        let mut total = 0;
+        let mut winning_copies = HashMap::new();
+        let mut ids_to_process = Vec::new();
+
+        for (i, card) in cards.iter().enumerate() {
+            let common_numbers = card.common_numbers();
+            let card_id = i + 1;
+            total += 1;
+
+            if !common_numbers.is_empty() {
+                let new_cards: Vec<usize> =
+                    ((card_id + 1)..=(card_id + common_numbers.len())).collect();
+                winning_copies.insert(card_id, new_cards.clone());
+                ids_to_process.extend(new_cards);
+            }
+        }
+
+        while let Some(id) = ids_to_process.pop() {
+            total += 1;
+            if let Some(ids) = winning_copies.get(&id) {
+                ids_to_process.extend(ids);
+            }
+        }
+
+        Ok(total)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn website_example_input() -> Vec<String> {
+        vec![
+            "Card 1: 41 48 83 86 17 | 83 86  6 31 17  9 48 53".to_string(),
+            "Card 2: 13 32 20 16 61 | 61 30 68 82 17 32 24 19".to_string(),
+            "Card 3:  1 21 53 59 44 | 69 82 63 72 16 21 14  1".to_string(),
+            "Card 4: 41 92 73 84 69 | 59 84 76 51 58  5 54 83".to_string(),
+            "Card 5: 87 83 26 28 32 | 88 30 70 12 93 22 82 36".to_string(),
+            "Card 6: 31 18 13 56 72 | 74 77 10 23 35 67 36 11".to_string(),
+        ]
+    }
+
+    /// Example from the Advent of Code website
+    /// https://adventofcode.com/2023/day/4
+    #[test]
+    fn website_example_part_1() {
+        let cards = Day4.parse(&mut website_example_input().iter()).unwrap();
+        assert_eq!(Day4.part_one(&cards).unwrap(), 13);
+    }
+
+    /// Example from the Advent of Code website
+    /// https://adventofcode.com/2023/day/4#part2
+    #[test]
+    fn website_example_part_2() {
+        let cards = Day4.parse(&mut website_example_input().iter()).unwrap();
+        assert_eq!(Day4.part_two(&cards).unwrap(), 30);
+    }
+}
@@ -0,0 +1,8 @@
+use std::process::ExitCode;
+
+use day_4::solver::Day4;
+use lib::fixtures::run_tests;
+
+fn main() -> ExitCode {
+    run_tests(Day4)
+}
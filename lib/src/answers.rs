@@ -0,0 +1,327 @@
+use std::{collections::BTreeMap, fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    input::Input,
+    solver::{Answer, Part},
+};
+
+/// Name of the answers file of each day, next to its `inputs/` directory
+pub const ANSWERS_FILE: &str = "answers.toml";
+/// Extension of the files with the expected answers of the input file with the
+/// same stem, one line per part. An empty line skips a part.
+pub const EXPECTED_EXTENSION: &str = "expected";
+
+/// Known correct answers of a day, by input path relative to its `inputs/`
+/// directory:
+///
+/// ```toml
+/// ["part-1.txt"]
+/// part_1 = 54597
+/// part_2 = 54504
+/// ```
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Answers {
+    inputs: BTreeMap<String, InputAnswers>,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InputAnswers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_1: Option<Answer>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_2: Option<Answer>,
+}
+
+impl InputAnswers {
+    /// Reads an expected answers file: part 1 on the first line, part 2 on the second
+    pub fn parse_expected(text: &str) -> Self {
+        let mut lines = text.lines().map(|line| match line.trim() {
+            "" => None,
+            answer => answer.parse().ok(),
+        });
+        Self {
+            part_1: lines.next().flatten(),
+            part_2: lines.next().flatten(),
+        }
+    }
+
+    pub fn get(&self, part: Part) -> Option<&Answer> {
+        match part {
+            Part::One => self.part_1.as_ref(),
+            Part::Two => self.part_2.as_ref(),
+        }
+    }
+
+    /// Contents of the expected answers file of these answers
+    pub fn to_expected(&self) -> String {
+        Part::ALL
+            .iter()
+            .map(|part| match self.get(*part) {
+                Some(answer) => format!("{}\n", answer),
+                None => "\n".to_string(),
+            })
+            .collect()
+    }
+}
+
+/// Result of checking an answer against the recorded one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: Answer },
+    Unknown,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Pass => write!(f, "pass"),
+            Verdict::Fail { expected } => write!(f, "FAIL, expected {}", expected),
+            Verdict::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+/// Key of an input in the answers file, its path relative to the day's
+/// `inputs` directory. Stdin and files outside of the directory have no key.
+pub fn input_key(input: &Input, inputs: &Path) -> Option<String> {
+    let Input::File(path) = input else {
+        return None;
+    };
+    let relative = match (path.canonicalize(), inputs.canonicalize()) {
+        (Ok(path), Ok(inputs)) => path.strip_prefix(inputs).ok()?.to_path_buf(),
+        _ => path.strip_prefix(inputs).ok()?.to_path_buf(),
+    };
+    let parts = relative
+        .components()
+        .map(|part| part.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>();
+    (!parts.is_empty()).then(|| parts.join("/"))
+}
+
+impl Answers {
+    /// Reads an answers file, a missing file has no answers
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(answers_error(path, e)),
+        };
+        Self::parse(&text).map_err(|e| answers_error(path, e))
+    }
+
+    /// Reads the answers of a day's crate: its answers file and the expected
+    /// answers files next to the inputs. A part answered by both is taken from
+    /// the expected answers file.
+    pub fn load_dir(crate_dir: &Path) -> Result<Self> {
+        let mut answers = Self::load(&crate_dir.join(ANSWERS_FILE))?;
+        let inputs = crate_dir.join("inputs");
+        let entries = match fs::read_dir(&inputs) {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(answers),
+            Err(e) => return Err(answers_error(&inputs, e)),
+        };
+
+        for entry in entries {
+            let path = entry.map_err(|e| answers_error(&inputs, e))?.path();
+            if path
+                .extension()
+                .is_some_and(|ext| ext == EXPECTED_EXTENSION)
+                || !path.is_file()
+            {
+                continue;
+            }
+            let expected = path.with_extension(EXPECTED_EXTENSION);
+            let text = match fs::read_to_string(&expected) {
+                Ok(text) => text,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(answers_error(&expected, e)),
+            };
+            if let Some(key) = input_key(&Input::File(path), &inputs) {
+                let expected = InputAnswers::parse_expected(&text);
+                for part in Part::ALL {
+                    if let Some(answer) = expected.get(part) {
+                        answers.record(&key, part, answer.clone());
+                    }
+                }
+            }
+        }
+        Ok(answers)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, toml::de::Error> {
+        toml::from_str(text)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = toml::to_string(self).map_err(|e| answers_error(path, e))?;
+        fs::write(path, text).map_err(|e| answers_error(path, e))
+    }
+
+    /// Keys of the inputs with recorded answers, see [`input_key`]
+    pub fn inputs(&self) -> impl Iterator<Item = &str> {
+        self.inputs.keys().map(String::as_str)
+    }
+
+    pub fn get(&self, input: &str, part: Part) -> Option<&Answer> {
+        self.inputs.get(input)?.get(part)
+    }
+
+    pub fn record(&mut self, input: &str, part: Part, answer: Answer) {
+        let answers = self.inputs.entry(input.to_string()).or_default();
+        match part {
+            Part::One => answers.part_1 = Some(answer),
+            Part::Two => answers.part_2 = Some(answer),
+        }
+    }
+
+    pub fn check(&self, input: &str, part: Part, answer: &Answer) -> Verdict {
+        match self.get(input, part) {
+            Some(expected) if expected.to_string() == answer.to_string() => Verdict::Pass,
+            Some(expected) => Verdict::Fail {
+                expected: expected.clone(),
+            },
+            None => Verdict::Unknown,
+        }
+    }
+}
+
+fn answers_error(path: &Path, source: impl std::error::Error + Send + Sync + 'static) -> Error {
+    Error::Answers {
+        path: path.display().to_string(),
+        source: Box::new(source),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::*;
+
+    const ANSWERS: &str = r#"
+["part-1.txt"]
+part_1 = 54597
+part_2 = "54504"
+
+["test.txt"]
+part_1 = 142
+"#;
+
+    #[test]
+    fn parses_numbers_and_text() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(
+            answers.inputs().collect::<Vec<&str>>(),
+            ["part-1.txt", "test.txt"]
+        );
+        assert_eq!(
+            answers.get("part-1.txt", Part::One),
+            Some(&Answer::Number(54597))
+        );
+        assert_eq!(
+            answers.get("part-1.txt", Part::Two),
+            Some(&Answer::Text("54504".to_string()))
+        );
+        assert_eq!(answers.get("test.txt", Part::Two), None);
+        assert_eq!(answers.get("other.txt", Part::One), None);
+    }
+
+    #[test]
+    fn checks_answers() {
+        let answers = Answers::parse(ANSWERS).unwrap();
+        assert_eq!(
+            answers.check("part-1.txt", Part::Two, &Answer::Number(54504)),
+            Verdict::Pass
+        );
+        assert_eq!(
+            answers.check("test.txt", Part::One, &Answer::Number(141)),
+            Verdict::Fail {
+                expected: Answer::Number(142)
+            }
+        );
+        assert_eq!(
+            answers.check("test.txt", Part::Two, &Answer::Number(1)),
+            Verdict::Unknown
+        );
+    }
+
+    #[test]
+    fn records_answers() {
+        let mut answers = Answers::default();
+        answers.record("test.txt", Part::Two, Answer::Number(281));
+        let text = toml::to_string(&answers).unwrap();
+        assert_eq!(text, "[\"test.txt\"]\npart_2 = 281\n");
+        assert_eq!(Answers::parse(&text).unwrap(), answers);
+    }
+
+    #[test]
+    fn uses_the_path_in_the_inputs_directory_as_key() {
+        let inputs = Path::new("day-1/inputs");
+        let input = |path: &str| Input::File(PathBuf::from(path));
+        assert_eq!(
+            input_key(&input("day-1/inputs/test.txt"), inputs),
+            Some("test.txt".to_string())
+        );
+        assert_eq!(
+            input_key(&input("day-1/inputs/extra/test.txt"), inputs),
+            Some("extra/test.txt".to_string())
+        );
+        assert_eq!(input_key(&input("/elsewhere/test.txt"), inputs), None);
+        assert_eq!(input_key(&Input::Stdin, inputs), None);
+    }
+
+    #[test]
+    fn parses_expected_files() {
+        let answers = InputAnswers::parse_expected("\n281\n");
+        assert_eq!(answers.part_1, None);
+        assert_eq!(answers.part_2, Some(Answer::Number(281)));
+        assert_eq!(answers.to_expected(), "\n281\n");
+
+        let answers = InputAnswers::parse_expected("4361");
+        assert_eq!(answers.part_1, Some(Answer::Number(4361)));
+        assert_eq!(answers.part_2, None);
+    }
+
+    #[test]
+    fn loads_expected_files_next_to_the_inputs() {
+        let dir = std::env::temp_dir().join(format!("aoc-answers-{}", std::process::id()));
+        fs::create_dir_all(dir.join("inputs")).unwrap();
+        fs::write(dir.join(ANSWERS_FILE), ANSWERS).unwrap();
+        fs::write(dir.join("inputs/test.txt"), "").unwrap();
+        fs::write(dir.join("inputs/test.expected"), "142\n281\n").unwrap();
+        fs::write(dir.join("inputs/part-1.txt"), "").unwrap();
+        fs::write(dir.join("inputs/part-1.expected"), "\n54504\n").unwrap();
+        fs::write(dir.join("inputs/lonely.expected"), "1\n").unwrap();
+
+        let answers = Answers::load_dir(&dir).unwrap();
+        assert_eq!(
+            answers.inputs().collect::<Vec<&str>>(),
+            ["part-1.txt", "test.txt"]
+        );
+        assert_eq!(
+            answers.get("test.txt", Part::Two),
+            Some(&Answer::Number(281))
+        );
+        // Merged per part, the expected answers file winning
+        assert_eq!(
+            answers.get("part-1.txt", Part::One),
+            Some(&Answer::Number(54597))
+        );
+        assert_eq!(
+            answers.get("part-1.txt", Part::Two),
+            Some(&Answer::Number(54504))
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn loads_missing_files_as_empty() {
+        let answers = Answers::load(Path::new("/missing/answers.toml")).unwrap();
+        assert_eq!(answers, Answers::default());
+    }
+}
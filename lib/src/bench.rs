@@ -0,0 +1,260 @@
+use std::{
+    fmt,
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use serde::Serialize;
+
+use crate::{error::Result, solver::Part};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BenchOptions {
+    /// Timed runs of each phase
+    pub iterations: usize,
+    /// Untimed runs of each phase before the timed ones
+    pub warmup: usize,
+}
+
+impl Default for BenchOptions {
+    fn default() -> Self {
+        Self {
+            iterations: 100,
+            warmup: 3,
+        }
+    }
+}
+
+/// Phase of a solution that is timed on its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    Parse,
+    PartOne,
+    PartTwo,
+}
+
+impl From<Part> for Phase {
+    fn from(part: Part) -> Self {
+        match part {
+            Part::One => Phase::PartOne,
+            Part::Two => Phase::PartTwo,
+        }
+    }
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Parse => write!(f, "parse"),
+            Phase::PartOne => write!(f, "part 1"),
+            Phase::PartTwo => write!(f, "part 2"),
+        }
+    }
+}
+
+/// Summary of the run times of a phase, in nanoseconds
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct Stats {
+    pub min_ns: u64,
+    pub median_ns: u64,
+    pub p95_ns: u64,
+    pub mean_ns: u64,
+}
+
+impl Stats {
+    /// Returns None when there are no samples
+    pub fn from_samples(samples: &[Duration]) -> Option<Self> {
+        if samples.is_empty() {
+            return None;
+        }
+        let mut nanos = samples
+            .iter()
+            .map(|sample| sample.as_nanos() as u64)
+            .collect::<Vec<u64>>();
+        nanos.sort_unstable();
+
+        Some(Self {
+            min_ns: nanos[0],
+            median_ns: percentile(&nanos, 0.5),
+            p95_ns: percentile(&nanos, 0.95),
+            mean_ns: nanos.iter().sum::<u64>() / nanos.len() as u64,
+        })
+    }
+}
+
+/// Nearest-rank percentile of sorted values
+fn percentile(sorted: &[u64], p: f64) -> u64 {
+    let rank = (p * sorted.len() as f64).ceil() as usize;
+    sorted[rank.clamp(1, sorted.len()) - 1]
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PhaseReport {
+    pub phase: Phase,
+    #[serde(flatten)]
+    pub stats: Stats,
+    /// Input bytes processed per second, at the median time
+    pub bytes_per_sec: f64,
+    /// Input lines processed per second, at the median time
+    pub lines_per_sec: f64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct BenchReport {
+    pub day: u8,
+    pub input: String,
+    pub bytes: usize,
+    pub lines: usize,
+    pub iterations: usize,
+    pub phases: Vec<PhaseReport>,
+}
+
+impl BenchReport {
+    pub fn new(day: u8, input: String, text: &str, options: &BenchOptions) -> Self {
+        Self {
+            day,
+            input,
+            bytes: text.len(),
+            lines: text.lines().count(),
+            iterations: options.iterations,
+            phases: Vec::new(),
+        }
+    }
+
+    pub fn add(&mut self, phase: Phase, samples: &[Duration]) {
+        let Some(stats) = Stats::from_samples(samples) else {
+            return;
+        };
+        let seconds = (stats.median_ns.max(1) as f64) / 1e9;
+        self.phases.push(PhaseReport {
+            phase,
+            stats,
+            bytes_per_sec: self.bytes as f64 / seconds,
+            lines_per_sec: self.lines as f64 / seconds,
+        });
+    }
+}
+
+impl fmt::Display for BenchReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Day {} {} ({} bytes, {} lines, {} iterations)",
+            self.day, self.input, self.bytes, self.lines, self.iterations
+        )?;
+        for phase in self.phases.iter() {
+            write!(
+                f,
+                "\n  {:<7} min {:>10}  median {:>10}  p95 {:>10}  {:>10}/s  {:>12} lines/s",
+                phase.phase.to_string(),
+                format_nanos(phase.stats.min_ns),
+                format_nanos(phase.stats.median_ns),
+                format_nanos(phase.stats.p95_ns),
+                format_bytes(phase.bytes_per_sec),
+                format!("{:.0}", phase.lines_per_sec),
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn format_nanos(nanos: u64) -> String {
+    match nanos {
+        0..=999 => format!("{}ns", nanos),
+        1_000..=999_999 => format!("{:.2}µs", nanos as f64 / 1e3),
+        1_000_000..=999_999_999 => format!("{:.2}ms", nanos as f64 / 1e6),
+        _ => format!("{:.2}s", nanos as f64 / 1e9),
+    }
+}
+
+fn format_bytes(bytes: f64) -> String {
+    if bytes >= 1e9 {
+        format!("{:.2} GB", bytes / 1e9)
+    } else if bytes >= 1e6 {
+        format!("{:.2} MB", bytes / 1e6)
+    } else if bytes >= 1e3 {
+        format!("{:.2} kB", bytes / 1e3)
+    } else {
+        format!("{:.0} B", bytes)
+    }
+}
+
+/// Runs `f` the warmup times and then times each of the iterations
+pub fn measure<T>(
+    options: &BenchOptions,
+    mut f: impl FnMut() -> Result<T>,
+) -> Result<Vec<Duration>> {
+    for _ in 0..options.warmup {
+        black_box(f()?);
+    }
+
+    let mut samples = Vec::with_capacity(options.iterations);
+    for _ in 0..options.iterations {
+        let start = Instant::now();
+        let result = f()?;
+        samples.push(start.elapsed());
+        black_box(result);
+    }
+    Ok(samples)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_stats() {
+        let samples = (1..=20)
+            .map(Duration::from_nanos)
+            .collect::<Vec<Duration>>();
+        let stats = Stats::from_samples(&samples).unwrap();
+        assert_eq!(
+            stats,
+            Stats {
+                min_ns: 1,
+                median_ns: 10,
+                p95_ns: 19,
+                mean_ns: 10,
+            }
+        );
+        assert_eq!(Stats::from_samples(&[]), None);
+    }
+
+    #[test]
+    fn measures_every_iteration() {
+        let options = BenchOptions {
+            iterations: 5,
+            warmup: 2,
+        };
+        let mut calls = 0;
+        let samples = measure(&options, || {
+            calls += 1;
+            Ok(calls)
+        })
+        .unwrap();
+        assert_eq!(samples.len(), 5);
+        assert_eq!(calls, 7);
+    }
+
+    #[test]
+    fn computes_throughput_at_the_median() {
+        let options = BenchOptions::default();
+        let mut report = BenchReport::new(1, "test".to_string(), "ab\ncd\n", &options);
+        report.add(Phase::Parse, &[Duration::from_millis(1); 3]);
+        assert_eq!(report.bytes, 6);
+        assert_eq!(report.lines, 2);
+        assert_eq!(report.phases[0].bytes_per_sec, 6000.0);
+        assert_eq!(report.phases[0].lines_per_sec, 2000.0);
+    }
+
+    #[test]
+    fn serializes_to_json() {
+        let mut report = BenchReport::new(2, "test".to_string(), "a\n", &BenchOptions::default());
+        report.add(Phase::PartOne, &[Duration::from_nanos(500)]);
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["day"], 2);
+        assert_eq!(json["phases"][0]["phase"], "part_one");
+        assert_eq!(json["phases"][0]["median_ns"], 500);
+    }
+}
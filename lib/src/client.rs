@@ -0,0 +1,390 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    thread,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use thiserror::Error;
+
+use crate::{
+    solver::{Answer, Part},
+    submit::Outcome,
+};
+
+/// Year of the puzzles
+pub const YEAR: u16 = 2023;
+pub const DEFAULT_BASE_URL: &str = "https://adventofcode.com";
+/// Environment variable overriding the base URL, for instance to use a local server
+pub const BASE_URL_ENV_VAR: &str = "AOC_BASE_URL";
+/// Environment variable with the `session` cookie of the website
+pub const SESSION_ENV_VAR: &str = "AOC_SESSION";
+/// Environment variable overriding the directory where the client keeps its state
+pub const STATE_DIR_ENV_VAR: &str = "AOC_STATE_DIR";
+/// Default time between two requests to the website
+pub const MIN_INTERVAL: Duration = Duration::from_secs(5);
+
+const USER_AGENT: &str = concat!(
+    "github.com/diegoasanch/advent-of-code-2023 ",
+    env!("CARGO_PKG_NAME"),
+    "/",
+    env!("CARGO_PKG_VERSION")
+);
+const LAST_REQUEST_FILE: &str = "last-request";
+
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error(
+        "No session token, set {} to the `session` cookie of the website",
+        SESSION_ENV_VAR
+    )]
+    MissingSession,
+
+    #[error("Request to {url} failed with status {status}: {body}")]
+    Status {
+        url: String,
+        status: u16,
+        body: String,
+    },
+
+    #[error("Request to {url} failed")]
+    Transport {
+        url: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("Could not use {path}")]
+    State {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+}
+
+/// Client of the Advent of Code website. Requests are spaced by at least
+/// [`MIN_INTERVAL`], also between runs, by keeping the time of the last
+/// request in the state directory.
+pub struct Client {
+    base_url: String,
+    session: Option<String>,
+    state_dir: PathBuf,
+    min_interval: Duration,
+    agent: ureq::Agent,
+}
+
+impl Client {
+    pub fn new(base_url: &str, session: Option<String>) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            session,
+            state_dir: default_state_dir(),
+            min_interval: MIN_INTERVAL,
+            agent: ureq::AgentBuilder::new()
+                .user_agent(USER_AGENT)
+                .timeout(Duration::from_secs(30))
+                .build(),
+        }
+    }
+
+    /// Client configured by the `AOC_BASE_URL`, `AOC_SESSION` and `AOC_STATE_DIR`
+    /// environment variables
+    pub fn from_env() -> Self {
+        let base_url = env::var(BASE_URL_ENV_VAR).unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+        let session = env::var(SESSION_ENV_VAR)
+            .ok()
+            .map(|session| session.trim().to_string())
+            .filter(|session| !session.is_empty());
+        let client = Self::new(&base_url, session);
+        match env::var_os(STATE_DIR_ENV_VAR) {
+            Some(dir) => client.with_state_dir(dir),
+            None => client,
+        }
+    }
+
+    pub fn with_state_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.state_dir = dir.into();
+        self
+    }
+
+    pub fn with_min_interval(mut self, min_interval: Duration) -> Self {
+        self.min_interval = min_interval;
+        self
+    }
+
+    pub fn has_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    pub fn day_url(&self, day: u8) -> String {
+        format!("{}/{}/day/{}", self.base_url, YEAR, day)
+    }
+
+    /// Downloads the input of a day into `path`. An existing file is never
+    /// fetched again.
+    pub fn fetch_input(&self, day: u8, path: &Path) -> Result<(), ClientError> {
+        if path.is_file() {
+            return Ok(());
+        }
+        let input = self.get(&format!("{}/input", self.day_url(day)))?;
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| state_error(parent, e))?;
+        }
+        // Written next to the final file first, so an interrupted download is never used
+        let partial = path.with_extension("part");
+        fs::write(&partial, input).map_err(|e| state_error(&partial, e))?;
+        fs::rename(&partial, path).map_err(|e| state_error(path, e))
+    }
+
+    /// Posts the answer of a part and reads the response of the website
+    pub fn submit(&self, day: u8, part: Part, answer: &Answer) -> Result<Outcome, ClientError> {
+        let url = format!("{}/answer", self.day_url(day));
+        let session = self.session.as_ref().ok_or(ClientError::MissingSession)?;
+        self.throttle()?;
+        let response = self
+            .agent
+            .post(&url)
+            .set("Cookie", &format!("session={}", session))
+            .send_form(&[
+                ("level", &part.to_string()),
+                ("answer", &answer.to_string()),
+            ]);
+        read_response(&url, response).map(|html| Outcome::parse(&html))
+    }
+
+    fn get(&self, url: &str) -> Result<String, ClientError> {
+        let session = self.session.as_ref().ok_or(ClientError::MissingSession)?;
+        self.throttle()?;
+        let response = self
+            .agent
+            .get(url)
+            .set("Cookie", &format!("session={}", session))
+            .call();
+        read_response(url, response)
+    }
+
+    /// Waits until the minimum interval since the last request has passed
+    fn throttle(&self) -> Result<(), ClientError> {
+        let path = self.state_dir.join(LAST_REQUEST_FILE);
+        let last_request = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| text.trim().parse::<u64>().ok())
+            .map(|millis| UNIX_EPOCH + Duration::from_millis(millis));
+
+        if let Some(last_request) = last_request {
+            let elapsed = SystemTime::now()
+                .duration_since(last_request)
+                .unwrap_or_default();
+            if elapsed < self.min_interval {
+                thread::sleep(self.min_interval - elapsed);
+            }
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        fs::create_dir_all(&self.state_dir).map_err(|e| state_error(&self.state_dir, e))?;
+        fs::write(&path, now.to_string()).map_err(|e| state_error(&path, e))
+    }
+}
+
+fn read_response(
+    url: &str,
+    response: Result<ureq::Response, ureq::Error>,
+) -> Result<String, ClientError> {
+    let read_body = |response: ureq::Response| {
+        response
+            .into_string()
+            .map_err(|source| ClientError::Transport {
+                url: url.to_string(),
+                source: Box::new(source),
+            })
+    };
+
+    match response {
+        Ok(response) => read_body(response),
+        Err(ureq::Error::Status(status, response)) => Err(ClientError::Status {
+            url: url.to_string(),
+            status,
+            body: read_body(response)?.trim().to_string(),
+        }),
+        Err(ureq::Error::Transport(source)) => Err(ClientError::Transport {
+            url: url.to_string(),
+            source: Box::new(source),
+        }),
+    }
+}
+
+fn state_error(path: &Path, source: io::Error) -> ClientError {
+    ClientError::State {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+/// `$XDG_CACHE_HOME/aoc-2023`, falling back to `~/.cache` and then the temporary directory
+fn default_state_dir() -> PathBuf {
+    let cache = env::var_os("XDG_CACHE_HOME")
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".cache")))
+        .unwrap_or_else(env::temp_dir);
+    cache.join(format!("aoc-{}", YEAR))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use std::{
+        io::{BufRead, BufReader, Read, Write},
+        net::TcpListener,
+        sync::mpsc,
+        time::Instant,
+    };
+
+    use super::*;
+
+    /// Request received by the [`serve`] stand-in server
+    #[derive(Debug)]
+    pub(crate) struct Request {
+        pub line: String,
+        pub cookie: Option<String>,
+        pub body: String,
+    }
+
+    /// Serves the given `(status, body)` responses, one per connection, on a
+    /// local port. Returns the base URL and the received requests.
+    pub(crate) fn serve(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<Request>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let (sender, receiver) = mpsc::channel();
+
+        thread::spawn(move || {
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+
+                let (mut cookie, mut length) = (None, 0);
+                loop {
+                    let mut header = String::new();
+                    reader.read_line(&mut header).unwrap();
+                    let header = header.trim_end();
+                    if header.is_empty() {
+                        break;
+                    }
+                    let (name, value) = header.split_once(':').unwrap();
+                    match name.to_ascii_lowercase().as_str() {
+                        "cookie" => cookie = Some(value.trim().to_string()),
+                        "content-length" => length = value.trim().parse().unwrap(),
+                        _ => {}
+                    }
+                }
+                let mut request_body = vec![0; length];
+                reader.read_exact(&mut request_body).unwrap();
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} Status\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+                sender
+                    .send(Request {
+                        line: line.trim_end().to_string(),
+                        cookie,
+                        body: String::from_utf8(request_body).unwrap(),
+                    })
+                    .unwrap();
+            }
+        });
+        (base_url, receiver)
+    }
+
+    /// Empty directory unique to a test
+    pub(crate) fn temp_dir(name: &str) -> PathBuf {
+        let dir = env::temp_dir().join(format!("aoc-client-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        dir
+    }
+
+    pub(crate) fn client(base_url: &str, state_dir: &Path) -> Client {
+        Client::new(base_url, Some("abc123".to_string()))
+            .with_state_dir(state_dir)
+            .with_min_interval(Duration::ZERO)
+    }
+
+    #[test]
+    fn fetches_the_input_once() {
+        let dir = temp_dir("fetch");
+        let (base_url, requests) = serve(vec![(200, "1abc2\npqr3stu8vwx\n")]);
+        let client = client(&base_url, &dir);
+        let path = dir.join("inputs/input.txt");
+
+        client.fetch_input(1, &path).unwrap();
+        assert_eq!(fs::read_to_string(&path).unwrap(), "1abc2\npqr3stu8vwx\n");
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "GET /2023/day/1/input HTTP/1.1");
+        assert_eq!(request.cookie.as_deref(), Some("session=abc123"));
+
+        // The server only answers once, a second request would fail
+        client.fetch_input(1, &path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn submits_answers() {
+        let dir = temp_dir("submit");
+        let page = "<main><article><p>That's the right answer!</p></article></main>";
+        let (base_url, requests) = serve(vec![(200, page)]);
+
+        let outcome = client(&base_url, &dir)
+            .submit(3, Part::Two, &Answer::Number(467835))
+            .unwrap();
+        assert_eq!(outcome, Outcome::Correct);
+        let request = requests.recv().unwrap();
+        assert_eq!(request.line, "POST /2023/day/3/answer HTTP/1.1");
+        assert_eq!(request.body, "level=2&answer=467835");
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_failed_requests() {
+        let dir = temp_dir("status");
+        let (base_url, _requests) = serve(vec![(404, "Not Found\n")]);
+        let path = dir.join("input.txt");
+
+        let error = client(&base_url, &dir).fetch_input(30, &path).unwrap_err();
+        assert!(matches!(
+            &error,
+            ClientError::Status { status: 404, body, .. } if body == "Not Found"
+        ));
+        assert!(!path.exists());
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn requires_a_session() {
+        let dir = temp_dir("session");
+        let client = Client::new("http://127.0.0.1:1", None).with_state_dir(&dir);
+        let error = client.fetch_input(1, &dir.join("input.txt")).unwrap_err();
+        assert!(matches!(error, ClientError::MissingSession));
+    }
+
+    #[test]
+    fn spaces_the_requests() {
+        let dir = temp_dir("throttle");
+        let client =
+            client("http://127.0.0.1:1", &dir).with_min_interval(Duration::from_millis(200));
+
+        client.throttle().unwrap();
+        let start = Instant::now();
+        client.throttle().unwrap();
+        assert!(start.elapsed() >= Duration::from_millis(150));
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+
+/// Byte range of a line of input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+}
+
+impl Span {
+    pub fn new(start: usize, end: usize) -> Self {
+        Self { start, end }
+    }
+
+    /// Span of `part` inside of `text`. `part` must be a slice of `text`, e.g.
+    /// the result of splitting or trimming it.
+    pub fn of(text: &str, part: &str) -> Self {
+        let start = (part.as_ptr() as usize).wrapping_sub(text.as_ptr() as usize);
+        debug_assert!(start + part.len() <= text.len(), "not a slice of the text");
+        Self::new(start, start + part.len())
+    }
+
+    /// Moves the span forward, used to make the span of a slice relative to the whole text
+    pub fn shift(self, offset: usize) -> Self {
+        Self::new(self.start + offset, self.end + offset)
+    }
+
+    pub fn len(&self) -> usize {
+        self.end - self.start
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start == self.end
+    }
+}
+
+/// Line of input with the span where an error was found
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Snippet {
+    /// 1-based line number
+    pub line_number: usize,
+    pub line: String,
+    pub span: Span,
+}
+
+impl Snippet {
+    pub fn new(line_number: usize, line: &str, span: Span) -> Self {
+        Self {
+            line_number,
+            line: line.to_string(),
+            span,
+        }
+    }
+
+    /// 1-based column where the span starts, counted in characters
+    pub fn column(&self) -> usize {
+        self.prefix().chars().count() + 1
+    }
+
+    /// Part of the line before the span
+    fn prefix(&self) -> &str {
+        let start = self.span.start.min(self.line.len());
+        self.line.get(..start).unwrap_or(&self.line)
+    }
+
+    /// Part of the line covered by the span
+    fn underlined(&self) -> &str {
+        let start = self.span.start.min(self.line.len());
+        let end = self.span.end.clamp(start, self.line.len());
+        self.line.get(start..end).unwrap_or_default()
+    }
+}
+
+/// Renders the line with the span underlined with carets, like rustc does:
+///
+/// ```text
+///   |
+/// 2 | Game 2: 3 blue, 4 yelow; 1 red
+///   |                   ^^^^^
+/// ```
+impl fmt::Display for Snippet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let number = self.line_number.to_string();
+        let gutter = " ".repeat(number.len());
+        // Keep the tabs so the carets line up with the text above them
+        let padding = self
+            .prefix()
+            .chars()
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect::<String>();
+        let carets = "^".repeat(self.underlined().chars().count().max(1));
+
+        writeln!(f, "{} |", gutter)?;
+        writeln!(f, "{} | {}", number, self.line)?;
+        write!(f, "{} | {}{}", gutter, padding, carets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gets_the_span_of_a_slice() {
+        let text = "Game 1: 3 blue";
+        let (_, counts) = text.split_once(':').unwrap();
+        assert_eq!(Span::of(text, counts), Span::new(7, 14));
+        assert_eq!(Span::of(text, counts.trim()), Span::new(8, 14));
+        assert_eq!(Span::of(text, counts.trim()).shift(2), Span::new(10, 16));
+    }
+
+    #[test]
+    fn renders_carets_under_the_span() {
+        let line = "Game 2: 3 blue, 4 yelow; 1 red";
+        let snippet = Snippet::new(2, line, Span::new(18, 23));
+        assert_eq!(snippet.column(), 19);
+        assert_eq!(
+            snippet.to_string(),
+            "  |\n\
+             2 | Game 2: 3 blue, 4 yelow; 1 red\n  \
+             |                   ^^^^^"
+        );
+    }
+
+    #[test]
+    fn renders_empty_spans_and_tabs() {
+        let snippet = Snippet::new(10, "\tab", Span::new(3, 3));
+        assert_eq!(snippet.column(), 4);
+        assert_eq!(snippet.to_string(), "   |\n10 | \tab\n   | \t  ^");
+    }
+
+    #[test]
+    fn counts_columns_in_characters() {
+        let line = "héllo wörld";
+        let snippet = Snippet::new(1, line, Span::of(line, &line[7..]));
+        assert_eq!(snippet.column(), 7);
+        assert!(snippet.to_string().ends_with("|       ^^^^^"));
+    }
+}
@@ -0,0 +1,234 @@
+use std::{fmt, io};
+
+use thiserror::Error;
+
+use crate::{
+    client::ClientError,
+    diagnostic::{Snippet, Span},
+    input::InputNotFound,
+    submit::Refusal,
+};
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Position of a parse error in the input, 1-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: Option<usize>,
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.column {
+            Some(column) => write!(f, "line {}, column {}", self.line, column),
+            None => write!(f, "line {}", self.line),
+        }
+    }
+}
+
+/// Error of the parsers of each day
+pub trait ParseError: std::error::Error + Send + Sync + 'static {
+    /// Bytes of the line that could not be parsed, if known
+    fn span(&self) -> Option<Span> {
+        None
+    }
+}
+
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    InputNotFound(#[from] InputNotFound),
+
+    #[error("{not_found}\nand it could not be downloaded")]
+    Fetch {
+        not_found: InputNotFound,
+        #[source]
+        source: ClientError,
+    },
+
+    #[error("Could not open the input {input}")]
+    Open {
+        input: String,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Could not read the input")]
+    Read(#[from] io::Error),
+
+    #[error("Invalid input{}", .location.map(|location| format!(" at {}", location)).unwrap_or_default())]
+    Parse {
+        location: Option<Location>,
+        /// Line of input that could not be parsed, to render the error
+        snippet: Option<Box<Snippet>>,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("No solution: {0}")]
+    NoSolution(String),
+
+    #[error("Could not use the answers file {path}")]
+    Answers {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error("Could not use the submission history {path}")]
+    History {
+        path: String,
+        #[source]
+        source: Box<dyn std::error::Error + Send + Sync>,
+    },
+
+    #[error(transparent)]
+    Client(#[from] ClientError),
+
+    #[error("Refusing to submit the answer")]
+    Refused(#[from] Refusal),
+
+    #[error("Could not create {path}")]
+    Scaffold {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+
+    #[error("Could not write the example {path}")]
+    Example {
+        path: String,
+        #[source]
+        source: io::Error,
+    },
+}
+
+impl Error {
+    /// Parse error without a known location
+    pub fn parse(source: impl ParseError) -> Self {
+        Error::Parse {
+            location: None,
+            snippet: None,
+            source: Box::new(source),
+        }
+    }
+
+    /// Parse error on a line of the input
+    pub fn parse_at(line_number: usize, line: &str, source: impl ParseError) -> Self {
+        let span = source.span();
+        Self::parse_span(line_number, line, span, source)
+    }
+
+    /// Parse error of a whole line of the input, underlined entirely unless
+    /// the source has a span of its own
+    pub fn parse_line(line_number: usize, line: &str, source: impl ParseError) -> Self {
+        let span = source.span().unwrap_or(Span::new(0, line.len()));
+        Self::parse_span(line_number, line, Some(span), source)
+    }
+
+    fn parse_span(
+        line_number: usize,
+        line: &str,
+        span: Option<Span>,
+        source: impl ParseError,
+    ) -> Self {
+        let snippet = span.map(|span| Box::new(Snippet::new(line_number, line, span)));
+        let location = Location {
+            line: line_number,
+            column: snippet.as_ref().map(|snippet| snippet.column()),
+        };
+        Error::Parse {
+            location: Some(location),
+            snippet,
+            source: Box::new(source),
+        }
+    }
+
+    /// Renders the error followed by the chain of its sources and, for parse
+    /// errors, the line of input with the invalid part underlined
+    pub fn report(&self) -> String {
+        let mut report = self.to_string();
+        let mut source = std::error::Error::source(self);
+        while let Some(error) = source {
+            report.push_str(&format!("\n  caused by: {}", error));
+            source = error.source();
+        }
+        if let Error::Parse {
+            snippet: Some(snippet),
+            ..
+        } = self
+        {
+            report.push_str(&format!("\n{}", snippet));
+        }
+        report
+    }
+}
+
+/// Attaches the line where a parser failed to its error
+pub trait ResultExt<T> {
+    fn at_line(self, line_number: usize, line: &str) -> Result<T>;
+}
+
+impl<T, E: ParseError> ResultExt<T> for std::result::Result<T, E> {
+    fn at_line(self, line_number: usize, line: &str) -> Result<T> {
+        self.map_err(|e| Error::parse_at(line_number, line, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Error, Debug)]
+    #[error("Invalid number: {0}")]
+    struct InvalidNumber(String, Option<Span>);
+
+    impl ParseError for InvalidNumber {
+        fn span(&self) -> Option<Span> {
+            self.1
+        }
+    }
+
+    #[test]
+    fn reports_the_error_chain_and_snippet() {
+        let line = "Card 1: 41 4x 83";
+        let result: std::result::Result<(), _> =
+            Err(InvalidNumber("4x".to_string(), Some(Span::new(11, 13))));
+        let error = result.at_line(2, line).unwrap_err();
+        assert_eq!(
+            error.report(),
+            "Invalid input at line 2, column 12\n  \
+             caused by: Invalid number: 4x\n  \
+             |\n\
+             2 | Card 1: 41 4x 83\n  \
+             |            ^^"
+        );
+    }
+
+    #[test]
+    fn reports_errors_without_span() {
+        let result: std::result::Result<(), _> = Err(InvalidNumber("4x".to_string(), None));
+        let error = result.at_line(3, "4x").unwrap_err();
+        assert_eq!(
+            error.report(),
+            "Invalid input at line 3\n  caused by: Invalid number: 4x"
+        );
+
+        let error = Error::parse(InvalidNumber("4x".to_string(), None));
+        assert_eq!(error.to_string(), "Invalid input");
+    }
+
+    #[test]
+    fn underlines_the_whole_line_without_span() {
+        let error = Error::parse_line(4, "abc", InvalidNumber("abc".to_string(), None));
+        assert_eq!(
+            error.report(),
+            "Invalid input at line 4, column 1\n  \
+             caused by: Invalid number: abc\n  \
+             |\n\
+             4 | abc\n  \
+             | ^^^"
+        );
+    }
+}
@@ -0,0 +1,223 @@
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    answers::{InputAnswers, EXPECTED_EXTENSION},
+    error::{Error, Result},
+    solver::{Answer, Part},
+};
+
+/// Example of a puzzle description and its answers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Example {
+    pub input: String,
+    pub part_1: Option<Answer>,
+    pub part_2: Option<Answer>,
+}
+
+impl Example {
+    /// Name of the input file of the nth example, 1-based
+    pub fn file_name(n: usize) -> String {
+        format!("example-{}.txt", n)
+    }
+
+    /// Number of the example of an input file name, see [`Example::file_name`]
+    fn number(file_name: &str) -> Option<usize> {
+        file_name
+            .strip_prefix("example-")?
+            .strip_suffix(".txt")?
+            .parse()
+            .ok()
+    }
+
+    fn answer_mut(&mut self, part: Part) -> &mut Option<Answer> {
+        match part {
+            Part::One => &mut self.part_1,
+            Part::Two => &mut self.part_2,
+        }
+    }
+}
+
+/// Reads the examples of a saved puzzle page. Each part is an `<article>`,
+/// its example is the first `<pre><code>` block and its answer the last
+/// emphasized `<code><em>` value. A part without its own example reuses the
+/// one of the previous part.
+pub fn extract(html: &str) -> Vec<Example> {
+    let mut examples: Vec<Example> = Vec::new();
+
+    for (part, article) in Part::ALL.into_iter().zip(articles(html)) {
+        if let Some(input) = between(article, "<pre><code>", "</code></pre>") {
+            examples.push(Example {
+                input: text(input),
+                part_1: None,
+                part_2: None,
+            });
+        }
+        let answer = article
+            .match_indices("<code><em>")
+            .filter_map(|(start, _)| between(&article[start..], "<code><em>", "</em></code>"))
+            .last();
+        if let (Some(example), Some(answer)) = (examples.last_mut(), answer) {
+            *example.answer_mut(part) = Some(text(answer).parse().unwrap_or_else(|e| match e {}));
+        }
+    }
+    examples
+}
+
+/// Writes the examples into the `inputs/` directory of a day's crate, each
+/// with an expected answers file. They are numbered after the examples
+/// already there, which are never overwritten. Returns the written files.
+pub fn write_examples(crate_dir: &Path, examples: &[Example]) -> Result<Vec<PathBuf>> {
+    let inputs = crate_dir.join("inputs");
+    fs::create_dir_all(&inputs).map_err(|e| write_error(&inputs, e))?;
+    let existing = fs::read_dir(&inputs)
+        .map_err(|e| write_error(&inputs, e))?
+        .filter_map(|entry| Example::number(&entry.ok()?.file_name().to_string_lossy()))
+        .max()
+        .unwrap_or(0);
+
+    let mut written = Vec::with_capacity(examples.len() * 2);
+    for (i, example) in examples.iter().enumerate() {
+        let path = inputs.join(Example::file_name(existing + i + 1));
+        let expected = path.with_extension(EXPECTED_EXTENSION);
+        let answers = InputAnswers {
+            part_1: example.part_1.clone(),
+            part_2: example.part_2.clone(),
+        };
+        create(&path, &example.input)?;
+        create(&expected, &answers.to_expected())?;
+        written.extend([path, expected]);
+    }
+    Ok(written)
+}
+
+/// Writes a new file, failing when it already exists
+fn create(path: &Path, contents: &str) -> Result<()> {
+    fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)
+        .and_then(|mut file| file.write_all(contents.as_bytes()))
+        .map_err(|e| write_error(path, e))
+}
+
+fn write_error(path: &Path, source: io::Error) -> Error {
+    Error::Example {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+/// Contents of the `<article>` elements
+fn articles(html: &str) -> impl Iterator<Item = &str> {
+    html.split("<article").skip(1).filter_map(|rest| {
+        let (_, article) = rest.split_once('>')?;
+        article.split_once("</article>").map(|(article, _)| article)
+    })
+}
+
+fn between<'a>(text: &'a str, start: &str, end: &str) -> Option<&'a str> {
+    let (_, rest) = text.split_once(start)?;
+    rest.split_once(end).map(|(inside, _)| inside)
+}
+
+/// Text of an HTML fragment, without tags and with the entities decoded
+pub(crate) fn text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len());
+    let mut in_tag = false;
+    for c in html.chars() {
+        match c {
+            '<' => in_tag = true,
+            '>' => in_tag = false,
+            c if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::answers::Answers;
+
+    use super::*;
+
+    const PAGE: &str = r#"<html><body><main>
+<article class="day-desc"><h2>--- Day 3: Gear Ratios ---</h2>
+<p>Here is an example engine schematic:</p>
+<pre><code>467..114..
+...*......
+..35..<em>633</em>.
+</code></pre>
+<p>Some numbers like <code>114</code> are not adjacent.
+The sum is <code><em>4361</em></code>.</p>
+</article>
+<p>Your puzzle answer was <code>519444</code>.</p>
+<article class="day-desc"><h2 id="part2">--- Part Two ---</h2>
+<p>A gear is any <code>*</code> symbol with a gear ratio of <code><em>16345</em></code>.
+Adding up all of the gear ratios produces <code><em>467835</em></code>.</p>
+</article>
+</main></body></html>"#;
+
+    #[test]
+    fn extracts_the_example_and_answers() {
+        assert_eq!(
+            extract(PAGE),
+            vec![Example {
+                input: "467..114..\n...*......\n..35..633.\n".to_string(),
+                part_1: Some(Answer::Number(4361)),
+                part_2: Some(Answer::Number(467835)),
+            }]
+        );
+    }
+
+    #[test]
+    fn extracts_a_new_example_per_part() {
+        let page = "<article><pre><code>1abc2\n</code></pre><code><em>12</em></code></article>\
+                    <article><pre><code>two1nine\n&lt;x&gt;\n</code></pre>\
+                    <code><em>29</em></code></article>";
+        let examples = extract(page);
+        assert_eq!(examples.len(), 2);
+        assert_eq!(examples[0].part_1, Some(Answer::Number(12)));
+        assert_eq!(examples[0].part_2, None);
+        assert_eq!(examples[1].input, "two1nine\n<x>\n");
+        assert_eq!(examples[1].part_1, None);
+        assert_eq!(examples[1].part_2, Some(Answer::Number(29)));
+    }
+
+    #[test]
+    fn writes_inputs_and_expected_answers() {
+        let dir = std::env::temp_dir().join(format!("aoc-examples-{}", std::process::id()));
+        let written = write_examples(&dir, &extract(PAGE)).unwrap();
+        assert_eq!(
+            written,
+            vec![
+                dir.join("inputs/example-1.txt"),
+                dir.join("inputs/example-1.expected")
+            ]
+        );
+
+        let answers = Answers::load_dir(&dir).unwrap();
+        assert_eq!(
+            answers.get("example-1.txt", Part::Two),
+            Some(&Answer::Number(467835))
+        );
+
+        // Written again after the existing ones
+        fs::write(dir.join("inputs/example-1.expected"), "1\n").unwrap();
+        let written = write_examples(&dir, &extract(PAGE)).unwrap();
+        assert_eq!(written[0], dir.join("inputs/example-2.txt"));
+        assert_eq!(
+            fs::read_to_string(dir.join("inputs/example-1.expected")).unwrap(),
+            "1\n"
+        );
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,86 @@
+use std::{path::PathBuf, process::ExitCode, sync::Arc};
+
+use libtest_mimic::{Arguments, Failed, Trial};
+
+use crate::{
+    answers::Answers,
+    error::Result,
+    input::Input,
+    solver::{Answer, Part, Solver},
+};
+
+/// Input of a day with a known answer for one of its parts
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fixture {
+    pub input: PathBuf,
+    pub part: Part,
+    pub expected: Answer,
+}
+
+impl Fixture {
+    /// Name of the test case, like `example-1.txt part 2`
+    pub fn name(&self) -> String {
+        let file = self.input.file_name().unwrap_or_default();
+        format!("{} part {}", file.to_string_lossy(), self.part)
+    }
+
+    /// Solves the part of the input
+    pub fn solve(&self, solver: &dyn Solver) -> Result<Answer> {
+        let mut reader = Input::File(self.input.clone()).reader()?;
+        Ok(solver.solve(reader.as_mut(), &[self.part])?.remove(0))
+    }
+}
+
+/// Every input of the day with known answers, from its answers file or the
+/// expected answers files next to the inputs
+pub fn fixtures(solver: &dyn Solver) -> Result<Vec<Fixture>> {
+    let answers = Answers::load_dir(solver.crate_dir())?;
+    let inputs = PathBuf::from(solver.inputs());
+
+    let mut fixtures = Vec::new();
+    for name in answers.inputs() {
+        for part in Part::ALL {
+            if let Some(expected) = answers.get(name, part) {
+                fixtures.push(Fixture {
+                    input: inputs.join(name),
+                    part,
+                    expected: expected.clone(),
+                });
+            }
+        }
+    }
+    Ok(fixtures)
+}
+
+/// Main function of the `fixtures` test of a day: runs one test case per
+/// fixture, with the usual test harness arguments
+pub fn run_tests(solver: impl Solver + Send + Sync + 'static) -> ExitCode {
+    let args = Arguments::from_args();
+    let fixtures = match fixtures(&solver) {
+        Ok(fixtures) => fixtures,
+        Err(e) => {
+            eprintln!("error: day {}: {}", solver.day(), e.report());
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let solver = Arc::new(solver);
+    let trials = fixtures
+        .into_iter()
+        .map(|fixture| {
+            let solver = Arc::clone(&solver);
+            Trial::test(fixture.name(), move || {
+                let answer = fixture.solve(solver.as_ref()).map_err(|e| e.report())?;
+                if answer.to_string() == fixture.expected.to_string() {
+                    Ok(())
+                } else {
+                    Err(Failed::from(format!(
+                        "expected {}, got {}",
+                        fixture.expected, answer
+                    )))
+                }
+            })
+        })
+        .collect();
+    libtest_mimic::run(&args, trials).exit_code()
+}
@@ -0,0 +1,326 @@
+use std::{
+    fmt,
+    ops::{Index, IndexMut},
+};
+
+use thiserror::Error;
+
+use crate::{diagnostic::Span, error::ParseError};
+
+/// Position of a cell in a [`Grid`], `x` grows to the right and `y` downwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+
+    /// Point moved by the given amounts, None when it would be negative
+    pub fn offset(self, dx: isize, dy: isize) -> Option<Self> {
+        Some(Self {
+            x: self.x.checked_add_signed(dx)?,
+            y: self.y.checked_add_signed(dy)?,
+        })
+    }
+}
+
+impl fmt::Display for Point {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({}, {})", self.x, self.y)
+    }
+}
+
+/// Offsets of the orthogonal neighbors: up, left, right, down
+pub const NEIGHBORS_4: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Offsets of the orthogonal and diagonal neighbors, row by row
+pub const NEIGHBORS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum GridError {
+    /// Row, 1-based, whose width differs from the first one
+    #[error("Row {row} is {found} cells wide, expected {expected}")]
+    Ragged {
+        row: usize,
+        expected: usize,
+        found: usize,
+        span: Span,
+    },
+
+    #[error("{cells} cells do not fill a grid {width} cells wide")]
+    Size { width: usize, cells: usize },
+}
+
+impl ParseError for GridError {
+    fn span(&self) -> Option<Span> {
+        match self {
+            GridError::Ragged { span, .. } => Some(*span),
+            GridError::Size { .. } => None,
+        }
+    }
+}
+
+/// Rectangular grid of cells stored row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Grid from its cells, row by row
+    pub fn new(width: usize, cells: Vec<T>) -> Result<Self, GridError> {
+        if !cells.len().is_multiple_of(width) {
+            return Err(GridError::Size {
+                width,
+                cells: cells.len(),
+            });
+        }
+        Ok(Self {
+            width,
+            height: cells.len().checked_div(width).unwrap_or(0),
+            cells,
+        })
+    }
+
+    /// Grid with a cell per character of each line, converted by `f`
+    pub fn parse_with<'a, E: From<GridError>>(
+        lines: impl IntoIterator<Item = &'a str>,
+        mut f: impl FnMut(Point, char) -> Result<T, E>,
+    ) -> Result<Self, E> {
+        let mut cells = Vec::new();
+        let mut width = None;
+
+        for (y, line) in lines.into_iter().enumerate() {
+            let start = cells.len();
+            for (x, character) in line.chars().enumerate() {
+                cells.push(f(Point::new(x, y), character)?);
+            }
+            let found = cells.len() - start;
+            match width {
+                None => width = Some(found),
+                Some(expected) if expected != found => {
+                    return Err(E::from(GridError::Ragged {
+                        row: y + 1,
+                        expected,
+                        found,
+                        span: Span::new(0, line.len()),
+                    }))
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(Self::new(width.unwrap_or(0), cells)?)
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.index_of(point).map(|index| &self.cells[index])
+    }
+
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.index_of(point).map(|index| &mut self.cells[index])
+    }
+
+    fn index_of(&self, point: Point) -> Option<usize> {
+        self.contains(point)
+            .then_some(point.y * self.width + point.x)
+    }
+
+    fn point_of(&self, index: usize) -> Point {
+        Point::new(index % self.width, index / self.width)
+    }
+
+    /// Cells with their positions, row by row
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| (self.point_of(index), cell))
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        (y < self.height).then(|| &self.cells[y * self.width..(y + 1) * self.width])
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // `chunks` panics on a zero width, which only happens on empty grids
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Cells of a column, from top to bottom. Empty when out of bounds.
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        self.ray(Point::new(x, 0), (0, 1)).map(|(_, cell)| cell)
+    }
+
+    /// Cells of the diagonal through a point, from its top left end
+    pub fn diagonal(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        let back = point.x.min(point.y);
+        self.ray(Point::new(point.x - back, point.y - back), (1, 1))
+    }
+
+    /// Cells of the anti-diagonal through a point, from its top right end
+    pub fn anti_diagonal(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        let back = self.width.saturating_sub(point.x + 1).min(point.y);
+        self.ray(Point::new(point.x + back, point.y - back), (-1, 1))
+    }
+
+    /// Cells from `start` moving in a direction until leaving the grid
+    pub fn ray(&self, start: Point, (dx, dy): (isize, isize)) -> impl Iterator<Item = (Point, &T)> {
+        let mut next = Some(start).filter(|point| self.contains(*point));
+        std::iter::from_fn(move || {
+            let point = next?;
+            next = point.offset(dx, dy).filter(|point| self.contains(*point));
+            Some((point, &self[point]))
+        })
+    }
+
+    /// Neighbors at the given offsets that are inside the grid
+    pub fn neighbors<'a>(
+        &'a self,
+        point: Point,
+        offsets: &'a [(isize, isize)],
+    ) -> impl Iterator<Item = (Point, &'a T)> + 'a {
+        offsets.iter().filter_map(move |(dx, dy)| {
+            let neighbor = point.offset(*dx, *dy)?;
+            self.get(neighbor).map(|cell| (neighbor, cell))
+        })
+    }
+
+    /// Orthogonal neighbors inside the grid
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbors(point, &NEIGHBORS_4)
+    }
+
+    /// Orthogonal and diagonal neighbors inside the grid
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = (Point, &T)> {
+        self.neighbors(point, &NEIGHBORS_8)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+}
+
+impl Grid<char> {
+    /// Grid with a cell per character of each line
+    pub fn parse<'a>(lines: impl IntoIterator<Item = &'a str>) -> Result<Self, GridError> {
+        Self::parse_with(lines, |_, character| Ok(character))
+    }
+}
+
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &Self::Output {
+        self.get(point)
+            .unwrap_or_else(|| panic!("{} is outside of the grid", point))
+    }
+}
+
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut Self::Output {
+        self.get_mut(point)
+            .unwrap_or_else(|| panic!("{} is outside of the grid", point))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<char> {
+        Grid::parse("abc\ndef\nghi\njkl".lines()).unwrap()
+    }
+
+    fn cells<'a>(cells: impl Iterator<Item = (Point, &'a char)>) -> String {
+        cells.map(|(_, cell)| *cell).collect()
+    }
+
+    #[test]
+    fn parses_and_indexes() {
+        let grid = grid();
+        assert_eq!((grid.width(), grid.height()), (3, 4));
+        assert_eq!(grid[Point::new(2, 1)], 'f');
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.get(Point::new(0, 4)), None);
+        assert_eq!(grid.row(3), Some(&['j', 'k', 'l'][..]));
+        assert_eq!(grid.row(4), None);
+        assert_eq!(grid.column(1).collect::<String>(), "behk");
+        assert_eq!(grid.column(3).count(), 0);
+    }
+
+    #[test]
+    fn fails_on_ragged_rows() {
+        let error = Grid::parse("abc\nde".lines()).unwrap_err();
+        assert_eq!(
+            error,
+            GridError::Ragged {
+                row: 2,
+                expected: 3,
+                found: 2,
+                span: Span::new(0, 2)
+            }
+        );
+        assert!(matches!(
+            Grid::new(2, vec![1, 2, 3]),
+            Err(GridError::Size { width: 2, cells: 3 })
+        ));
+    }
+
+    #[test]
+    fn iterates_neighbors_inside_the_grid() {
+        let grid = grid();
+        assert_eq!(cells(grid.neighbors8(Point::new(1, 1))), "abcdfghi");
+        assert_eq!(cells(grid.neighbors8(Point::new(0, 0))), "bde");
+        assert_eq!(cells(grid.neighbors4(Point::new(1, 1))), "bdfh");
+        assert_eq!(cells(grid.neighbors4(Point::new(2, 3))), "ik");
+    }
+
+    #[test]
+    fn iterates_diagonals() {
+        let grid = grid();
+        assert_eq!(cells(grid.diagonal(Point::new(2, 3))), "dhl");
+        assert_eq!(cells(grid.diagonal(Point::new(1, 1))), "aei");
+        assert_eq!(cells(grid.anti_diagonal(Point::new(1, 1))), "ceg");
+        assert_eq!(cells(grid.anti_diagonal(Point::new(0, 3))), "fhj");
+        assert_eq!(cells(grid.ray(Point::new(0, 3), (1, -1))), "jhf");
+    }
+
+    #[test]
+    fn maps_and_mutates_cells() {
+        let mut grid = grid().map(|cell| *cell as u8 - b'a');
+        grid[Point::new(0, 0)] = 42;
+        assert_eq!(grid.get(Point::new(0, 0)), Some(&42));
+        assert_eq!(grid.iter().last(), Some((Point::new(2, 3), &11)));
+    }
+}
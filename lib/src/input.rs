@@ -1,12 +1,299 @@
-use std::fs;
+use std::{
+    env, fmt,
+    fs::{self, File},
+    io::{self, BufRead, BufReader, Read, StdinLock},
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+/// Size of the read buffer of a [`LineReader`]
+const BUFFER_SIZE: usize = 64 * 1024;
+
+/// Environment variable with the input path. `{day}` is replaced by the day number.
+pub const INPUT_ENV_VAR: &str = "AOC_INPUT";
+
+/// File names looked up inside an inputs directory, in order
+pub const INPUT_FILE_NAMES: [&str; 2] = ["input.txt", "part-1.txt"];
 
 /// Read the contents of a file into a string.
-pub fn read_file(path: &str) -> Result<String, std::io::Error> {
+pub fn read_file(path: impl AsRef<Path>) -> Result<String, std::io::Error> {
     fs::read_to_string(path)
 }
 
 /// Read the contents of a file into a vector of strings, one per line.
-pub fn read_file_lines(path: &str) -> Result<Vec<String>, std::io::Error> {
+pub fn read_file_lines(path: impl AsRef<Path>) -> Result<Vec<String>, std::io::Error> {
     let contents = read_file(path)?;
     Ok(contents.lines().map(|s| s.to_string()).collect())
 }
+
+/// Source of input lines, read one at a time.
+///
+/// Each line is borrowed until the next one is requested, so the whole input
+/// never has to be held in memory.
+pub trait LineSource {
+    /// Returns the next line without its line ending, or `None` at the end of the input.
+    fn next_line(&mut self) -> io::Result<Option<&str>>;
+}
+
+/// Buffered reader that yields the lines of any [`BufRead`], reusing a single line buffer.
+pub struct LineReader<R> {
+    reader: R,
+    line: String,
+}
+
+impl<R: BufRead> LineReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            line: String::new(),
+        }
+    }
+}
+
+impl<R: Read> LineReader<BufReader<R>> {
+    /// Wraps an unbuffered reader.
+    pub fn from_read(read: R) -> Self {
+        Self::new(BufReader::with_capacity(BUFFER_SIZE, read))
+    }
+}
+
+impl LineReader<BufReader<File>> {
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::from_read(File::open(path)?))
+    }
+}
+
+impl LineReader<StdinLock<'static>> {
+    pub fn stdin() -> Self {
+        Self::new(io::stdin().lock())
+    }
+}
+
+impl<R: BufRead> LineSource for LineReader<R> {
+    fn next_line(&mut self) -> io::Result<Option<&str>> {
+        self.line.clear();
+        if self.reader.read_line(&mut self.line)? == 0 {
+            return Ok(None);
+        }
+
+        let line = self.line.strip_suffix('\n').unwrap_or(&self.line);
+        Ok(Some(line.strip_suffix('\r').unwrap_or(line)))
+    }
+}
+
+impl LineSource for std::slice::Iter<'_, String> {
+    fn next_line(&mut self) -> io::Result<Option<&str>> {
+        Ok(self.next().map(String::as_str))
+    }
+}
+
+impl LineSource for std::str::Lines<'_> {
+    fn next_line(&mut self) -> io::Result<Option<&str>> {
+        Ok(self.next())
+    }
+}
+
+/// Calls `f` with each remaining line and its 1-based line number, stopping at the first error.
+pub fn try_for_each_line<E: From<io::Error>>(
+    lines: &mut dyn LineSource,
+    mut f: impl FnMut(usize, &str) -> Result<(), E>,
+) -> Result<(), E> {
+    let mut line_number = 0;
+    while let Some(line) = lines.next_line()? {
+        line_number += 1;
+        f(line_number, line)?;
+    }
+    Ok(())
+}
+
+/// Reads the remaining lines of a source into a vector of strings, one per line.
+pub fn collect_lines(lines: &mut dyn LineSource) -> io::Result<Vec<String>> {
+    let mut result = Vec::new();
+    while let Some(line) = lines.next_line()? {
+        result.push(line.to_string());
+    }
+    Ok(result)
+}
+
+/// Where the input of a puzzle is read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Input {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Input {
+    /// `-` means stdin, anything else is a file path
+    pub fn from_arg(arg: &str) -> Self {
+        match arg {
+            "-" => Input::Stdin,
+            path => Input::File(PathBuf::from(path)),
+        }
+    }
+
+    pub fn read_to_string(&self) -> io::Result<String> {
+        match self {
+            Input::Stdin => io::read_to_string(io::stdin()),
+            Input::File(path) => read_file(path),
+        }
+    }
+
+    /// Buffered reader of the raw bytes of the input
+    pub fn reader(&self) -> io::Result<Box<dyn BufRead>> {
+        match self {
+            Input::Stdin => Ok(Box::new(io::stdin().lock())),
+            Input::File(path) => Ok(Box::new(BufReader::with_capacity(
+                BUFFER_SIZE,
+                File::open(path)?,
+            ))),
+        }
+    }
+
+    pub fn lines(&self) -> io::Result<Box<dyn LineSource>> {
+        Ok(Box::new(LineReader::new(self.reader()?)))
+    }
+}
+
+impl fmt::Display for Input {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Input::Stdin => write!(f, "stdin"),
+            Input::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+#[derive(Error, Debug, PartialEq, Eq)]
+#[error("No input found for day {day}, tried:{}", list_places(.tried))]
+pub struct InputNotFound {
+    pub day: u8,
+    pub tried: Vec<String>,
+}
+
+fn list_places(places: &[String]) -> String {
+    places
+        .iter()
+        .map(|place| format!("\n  - {}", place))
+        .collect()
+}
+
+/// Finds the input of a day. The first of these is used:
+/// - The explicit argument (`-` for stdin)
+/// - The `AOC_INPUT` environment variable
+/// - An `input.txt` or `part-1.txt` file in the day's `inputs/` directory, which
+///   is looked up in the day's crate, the current directory and `day-N/` under the current directory
+pub fn find_input(day: u8, arg: Option<&str>, crate_inputs: &Path) -> Result<Input, InputNotFound> {
+    find_input_from(day, arg, env::var(INPUT_ENV_VAR).ok(), crate_inputs)
+}
+
+fn find_input_from(
+    day: u8,
+    arg: Option<&str>,
+    env_input: Option<String>,
+    crate_inputs: &Path,
+) -> Result<Input, InputNotFound> {
+    if let Some(arg) = arg {
+        return Ok(Input::from_arg(arg));
+    }
+    if let Some(env_input) = env_input {
+        return Ok(Input::from_arg(
+            &env_input.replace("{day}", &day.to_string()),
+        ));
+    }
+
+    let mut tried = vec![format!("{} environment variable (not set)", INPUT_ENV_VAR)];
+    let directories = [
+        crate_inputs.to_path_buf(),
+        PathBuf::from("inputs"),
+        PathBuf::from(format!("day-{}", day)).join("inputs"),
+    ];
+    for directory in directories.iter() {
+        for name in INPUT_FILE_NAMES {
+            let path = directory.join(name);
+            if path.is_file() {
+                return Ok(Input::File(path));
+            }
+            tried.push(path.display().to_string());
+        }
+    }
+
+    Err(InputNotFound { day, tried })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn reads_lines_without_line_endings() {
+        let mut reader = LineReader::new(Cursor::new("one\ntwo\r\n\nthree"));
+        assert_eq!(reader.next_line().unwrap(), Some("one"));
+        assert_eq!(reader.next_line().unwrap(), Some("two"));
+        assert_eq!(reader.next_line().unwrap(), Some(""));
+        assert_eq!(reader.next_line().unwrap(), Some("three"));
+        assert_eq!(reader.next_line().unwrap(), None);
+        assert_eq!(reader.next_line().unwrap(), None);
+    }
+
+    #[test]
+    fn collects_the_same_lines_as_str_lines() {
+        let text = "467..114..\r\n...*......\n..35..633.\n";
+        let mut reader = LineReader::from_read(text.as_bytes());
+        let expected = text.lines().map(|s| s.to_string()).collect::<Vec<String>>();
+        assert_eq!(collect_lines(&mut reader).unwrap(), expected);
+        assert_eq!(collect_lines(&mut expected.iter()).unwrap(), expected);
+    }
+
+    #[test]
+    fn fails_on_invalid_utf8() {
+        let mut reader = LineReader::new(Cursor::new(vec![b'a', 0xff, b'\n']));
+        assert!(reader.next_line().is_err());
+    }
+
+    #[test]
+    fn parses_input_args() {
+        assert_eq!(Input::from_arg("-"), Input::Stdin);
+        assert_eq!(
+            Input::from_arg("inputs/test.txt"),
+            Input::File(PathBuf::from("inputs/test.txt"))
+        );
+    }
+
+    #[test]
+    fn prefers_the_explicit_input() {
+        let input = find_input_from(3, Some("-"), Some("env.txt".to_string()), Path::new("x"));
+        assert_eq!(input, Ok(Input::Stdin));
+
+        let input = find_input_from(3, None, Some("day-{day}.txt".to_string()), Path::new("x"));
+        assert_eq!(input, Ok(Input::File(PathBuf::from("day-3.txt"))));
+    }
+
+    #[test]
+    fn finds_input_in_the_crate_inputs() {
+        let crate_inputs = env::temp_dir().join(format!("aoc-inputs-{}", std::process::id()));
+        assert!(find_input_from(42, None, None, &crate_inputs).is_err());
+
+        fs::create_dir_all(&crate_inputs).unwrap();
+        fs::write(crate_inputs.join("part-1.txt"), "").unwrap();
+        let input = find_input_from(42, None, None, &crate_inputs);
+        assert_eq!(input, Ok(Input::File(crate_inputs.join("part-1.txt"))));
+
+        fs::write(crate_inputs.join("input.txt"), "").unwrap();
+        let input = find_input_from(42, None, None, &crate_inputs);
+        assert_eq!(input, Ok(Input::File(crate_inputs.join("input.txt"))));
+        fs::remove_dir_all(&crate_inputs).unwrap();
+    }
+
+    #[test]
+    fn lists_the_places_tried() {
+        let error = find_input_from(42, None, None, Path::new("/missing")).unwrap_err();
+        assert_eq!(error.tried.len(), 7);
+        let message = error.to_string();
+        assert!(message.starts_with("No input found for day 42, tried:\n  - AOC_INPUT"));
+        assert!(message.contains("/missing/input.txt"));
+        assert!(message.contains("day-42/inputs/part-1.txt"));
+    }
+}
@@ -0,0 +1,226 @@
+use std::marker::PhantomData;
+
+use thiserror::Error;
+
+use crate::{diagnostic::Span, error::ParseError, parse::SyntaxError};
+
+/// Integer that could not be read, see [`crate::parse::Segment::integers`]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum IntegerError {
+    #[error("{text} does not fit in {type_name}")]
+    Overflow {
+        text: String,
+        type_name: &'static str,
+        span: Span,
+    },
+
+    #[error(transparent)]
+    Syntax(#[from] SyntaxError),
+}
+
+impl IntegerError {
+    /// Moves the span forward, see [`Span::shift`]
+    pub(crate) fn shift(self, offset: usize) -> Self {
+        match self {
+            IntegerError::Overflow {
+                text,
+                type_name,
+                span,
+            } => IntegerError::Overflow {
+                text,
+                type_name,
+                span: span.shift(offset),
+            },
+            IntegerError::Syntax(error) => IntegerError::Syntax(SyntaxError {
+                span: error.span.shift(offset),
+                ..error
+            }),
+        }
+    }
+}
+
+impl ParseError for IntegerError {
+    fn span(&self) -> Option<Span> {
+        match self {
+            IntegerError::Overflow { span, .. } => Some(*span),
+            IntegerError::Syntax(error) => error.span(),
+        }
+    }
+}
+
+/// Primitive integer that can be read from its decimal digits
+pub trait Integer: Copy {
+    /// Whether a `-` right before the digits makes the number negative
+    const SIGNED: bool;
+
+    /// Number of the ASCII digits, None on overflow
+    fn from_digits(negative: bool, digits: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_integer {
+    ($signed:literal => $($t:ty),*) => {$(
+        impl Integer for $t {
+            const SIGNED: bool = $signed;
+
+            fn from_digits(negative: bool, digits: &[u8]) -> Option<Self> {
+                // Negative numbers are accumulated downwards so that MIN fits
+                digits.iter().try_fold(0 as $t, |number, digit| {
+                    let digit = (digit - b'0') as $t;
+                    let number = number.checked_mul(10)?;
+                    if negative {
+                        number.checked_sub(digit)
+                    } else {
+                        number.checked_add(digit)
+                    }
+                })
+            }
+        }
+    )*};
+}
+
+impl_integer!(false => u8, u16, u32, u64, u128, usize);
+impl_integer!(true => i8, i16, i32, i64, i128, isize);
+
+/// Iterator over the integers of a text with their spans, see [`integers`]
+#[derive(Debug, Clone)]
+pub(crate) struct Integers<'a, T> {
+    bytes: &'a [u8],
+    position: usize,
+    integer: PhantomData<T>,
+}
+
+impl<T: Integer> Iterator for Integers<'_, T> {
+    type Item = Result<(T, Span), IntegerError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start = self.position + next_digit(&self.bytes[self.position..])?;
+        let end = start
+            + self.bytes[start..]
+                .iter()
+                .position(|byte| !byte.is_ascii_digit())
+                .unwrap_or(self.bytes.len() - start);
+        let negative = T::SIGNED && start > self.position && self.bytes[start - 1] == b'-';
+        let span = Span::new(if negative { start - 1 } else { start }, end);
+        self.position = end;
+
+        Some(match T::from_digits(negative, &self.bytes[start..end]) {
+            Some(number) => Ok((number, span)),
+            None => Err(IntegerError::Overflow {
+                text: String::from_utf8_lossy(&self.bytes[span.start..span.end]).into_owned(),
+                type_name: std::any::type_name::<T>(),
+                span,
+            }),
+        })
+    }
+}
+
+/// Integers of a text with their spans, skipping everything that is not a
+/// digit. For signed types a `-` right before the digits is a minus sign,
+/// unsigned types treat it as a separator, so `3-5` is a range.
+pub(crate) fn integers<T: Integer>(text: &(impl AsRef<[u8]> + ?Sized)) -> Integers<'_, T> {
+    Integers {
+        bytes: text.as_ref(),
+        position: 0,
+        integer: PhantomData,
+    }
+}
+
+/// Index of the first ASCII digit
+fn next_digit(bytes: &[u8]) -> Option<usize> {
+    if cfg!(feature = "simd") {
+        next_digit_swar(bytes)
+    } else {
+        bytes.iter().position(u8::is_ascii_digit)
+    }
+}
+
+/// [`next_digit`] checking 8 bytes at a time with bitwise operations on a `u64`
+fn next_digit_swar(bytes: &[u8]) -> Option<usize> {
+    const ONES: u64 = u64::MAX / 255;
+    const HIGH_BITS: u64 = ONES * 0x80;
+
+    let mut chunks = bytes.chunks_exact(8);
+    for (i, chunk) in chunks.by_ref().enumerate() {
+        let word = u64::from_le_bytes(chunk.try_into().unwrap());
+        // Without their high bit the bytes can be increased without carrying
+        // into the next one, their high bit then tells how they compare
+        let low = word & !HIGH_BITS;
+        let at_least_0 = low + ONES * (0x80 - b'0' as u64);
+        let above_9 = low + ONES * (0x80 - b':' as u64);
+        let digits = at_least_0 & !above_9 & !word & HIGH_BITS;
+        if digits != 0 {
+            return Some(i * 8 + digits.trailing_zeros() as usize / 8);
+        }
+    }
+    let rest = chunks.remainder();
+    rest.iter()
+        .position(u8::is_ascii_digit)
+        .map(|index| bytes.len() - rest.len() + index)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Values of the integers of a text
+    fn all_integers<T: Integer>(
+        text: &(impl AsRef<[u8]> + ?Sized),
+    ) -> Result<Vec<T>, IntegerError> {
+        integers(text)
+            .map(|integer| integer.map(|(number, _)| number))
+            .collect()
+    }
+
+    #[test]
+    fn extracts_integers_with_their_spans() {
+        let line = "Card  1: 41 48 | 83  6";
+        let found = integers::<u8>(line).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(found[0], (1, Span::new(6, 7)));
+        assert_eq!(found[3], (83, Span::new(17, 19)));
+        assert_eq!(
+            all_integers::<u32>(line.as_bytes()),
+            Ok(vec![1, 41, 48, 83, 6])
+        );
+        assert_eq!(all_integers::<u64>(""), Ok(vec![]));
+    }
+
+    #[test]
+    fn reads_minus_signs_for_signed_types_only() {
+        assert_eq!(all_integers::<i32>("x=-3, y=10-2"), Ok(vec![-3, 10, -2]));
+        assert_eq!(all_integers::<u32>("x=-3, y=10-2"), Ok(vec![3, 10, 2]));
+        assert_eq!(
+            integers::<i8>("-128").next(),
+            Some(Ok((i8::MIN, Span::new(0, 4))))
+        );
+    }
+
+    #[test]
+    fn reports_overflows() {
+        assert_eq!(
+            all_integers::<u8>("12 256"),
+            Err(IntegerError::Overflow {
+                text: "256".to_string(),
+                type_name: "u8",
+                span: Span::new(3, 6)
+            })
+        );
+        let error = all_integers::<i8>("-129").unwrap_err();
+        assert_eq!(error.to_string(), "-129 does not fit in i8");
+        assert_eq!(error.span(), Some(Span::new(0, 4)));
+        assert_eq!(all_integers::<u128>(&"9".repeat(38)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn finds_digits_a_word_at_a_time() {
+        let text = "abcdefgh/:\u{e9}\u{ff10}ijklmnopq7rs0";
+        for start in 0..text.len() {
+            let bytes = &text.as_bytes()[start..];
+            assert_eq!(
+                next_digit_swar(bytes),
+                bytes.iter().position(u8::is_ascii_digit)
+            );
+        }
+        assert_eq!(next_digit_swar(b"0"), Some(0));
+        assert_eq!(next_digit_swar(b"        "), None);
+    }
+}
@@ -1 +1,16 @@
+pub mod answers;
+pub mod bench;
+pub mod client;
+pub mod diagnostic;
+pub mod error;
+pub mod examples;
+#[cfg(feature = "fixtures")]
+pub mod fixtures;
+pub mod grid;
 pub mod input;
+pub mod integers;
+pub mod parse;
+pub mod patterns;
+pub mod scaffold;
+pub mod solver;
+pub mod submit;
@@ -0,0 +1,260 @@
+use std::str::FromStr;
+
+use thiserror::Error;
+
+use crate::{
+    diagnostic::Span,
+    error::ParseError,
+    integers::{integers, Integer, IntegerError},
+};
+
+/// Part of a line that did not have the expected format
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Expected {expected}, found {found:?}")]
+pub struct SyntaxError {
+    pub expected: String,
+    pub found: String,
+    pub span: Span,
+}
+
+impl ParseError for SyntaxError {
+    fn span(&self) -> Option<Span> {
+        Some(self.span)
+    }
+}
+
+/// Part of a line being parsed. It knows where it is in the line, so the
+/// errors point at the invalid part of the whole line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Segment<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> Segment<'a> {
+    /// Segment covering a whole line
+    pub fn new(line: &'a str) -> Self {
+        Self {
+            text: line,
+            offset: 0,
+        }
+    }
+
+    /// Segment of a part of this one
+    fn sub(&self, part: &'a str) -> Self {
+        Self {
+            text: part,
+            offset: self.offset + Span::of(self.text, part).start,
+        }
+    }
+
+    pub fn as_str(&self) -> &'a str {
+        self.text
+    }
+
+    /// Bytes of the line covered by the segment
+    pub fn span(&self) -> Span {
+        Span::new(self.offset, self.offset + self.text.len())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    pub fn trim(self) -> Self {
+        self.sub(self.text.trim())
+    }
+
+    /// Error saying that this segment is not what was expected
+    pub fn error(&self, expected: impl Into<String>) -> SyntaxError {
+        SyntaxError {
+            expected: expected.into(),
+            found: self.text.to_string(),
+            span: self.span(),
+        }
+    }
+
+    /// Parts before and after the first separator
+    pub fn split_once(self, separator: char) -> Result<(Self, Self), SyntaxError> {
+        match self.text.split_once(separator) {
+            Some((before, after)) => Ok((self.sub(before), self.sub(after))),
+            None => Err(self.trim().error(format!("`{}`", separator))),
+        }
+    }
+
+    /// Reads `<label> <value><separator><rest>`, like `Game 12: ...`, into the
+    /// trimmed value and the rest
+    pub fn labelled(self, label: &str, separator: char) -> Result<(Self, Self), SyntaxError> {
+        let (head, rest) = self.split_once(separator)?;
+        let head = head.trim();
+        let value = head
+            .text
+            .strip_prefix(label)
+            .map(|value| head.sub(value).trim())
+            .filter(|value| !value.is_empty())
+            .ok_or_else(|| head.error(format!("`{} <value>`", label)))?;
+        Ok((value, rest))
+    }
+
+    /// Trimmed parts between the separators
+    pub fn split(self, separator: char) -> impl Iterator<Item = Segment<'a>> {
+        self.text
+            .split(separator)
+            .map(move |part| self.sub(part).trim())
+    }
+
+    /// Parts separated by any amount of whitespace
+    pub fn words(self) -> impl Iterator<Item = Segment<'a>> {
+        self.text.split_whitespace().map(move |word| self.sub(word))
+    }
+
+    /// Parses the trimmed segment, `expected` describes it in the error
+    pub fn parse<T: FromStr>(self, expected: &str) -> Result<T, SyntaxError> {
+        let trimmed = self.trim();
+        trimmed.text.parse().map_err(|_| trimmed.error(expected))
+    }
+
+    /// Parses each of the parts between the separators
+    pub fn list<T, E>(
+        self,
+        separator: char,
+        f: impl FnMut(Segment<'a>) -> Result<T, E>,
+    ) -> Result<Vec<T>, E> {
+        self.split(separator).map(f).collect()
+    }
+
+    /// Integers separated by any amount of whitespace, found with the
+    /// byte-level scan of [`crate::integers`]
+    pub fn integers<T: Integer>(self) -> Result<Vec<T>, IntegerError> {
+        let mut numbers = Vec::new();
+        let mut end = 0;
+        for integer in integers::<T>(self.text) {
+            let (number, span) = integer.map_err(|error| error.shift(self.offset))?;
+            let separator = &self.text[end..span.start];
+            if !separator.trim().is_empty() || (end > 0 && separator.is_empty()) {
+                return Err(self.word_at(end).error("an integer").into());
+            }
+            numbers.push(number);
+            end = span.end;
+        }
+        if !self.text[end..].trim().is_empty() {
+            return Err(self.word_at(end).error("an integer").into());
+        }
+        Ok(numbers)
+    }
+
+    /// First word ending after the given index of the segment
+    fn word_at(&self, index: usize) -> Self {
+        self.words()
+            .find(|word| word.span().end > self.offset + index)
+            .unwrap_or(*self)
+    }
+
+    /// Reads exactly two words, like `3 blue`
+    pub fn pair(self, expected: &str) -> Result<(Self, Self), SyntaxError> {
+        let mut words = self.words();
+        match (words.next(), words.next(), words.next()) {
+            (Some(first), Some(second), None) => Ok((first, second)),
+            _ => Err(self.trim().error(expected)),
+        }
+    }
+
+    /// Reads a `<key><assign><value>` record, like `red=3`, into its trimmed key and value
+    pub fn key_value(self, assign: char) -> Result<(Self, Self), SyntaxError> {
+        let (key, value) = self.split_once(assign)?;
+        Ok((key.trim(), value.trim()))
+    }
+
+    /// Reads records separated by `separator`, each being a key and a value
+    /// separated by `assign`, or by whitespace when there is none
+    pub fn records(
+        self,
+        separator: char,
+        assign: Option<char>,
+    ) -> Result<Vec<(Self, Self)>, SyntaxError> {
+        self.list(separator, |record| match assign {
+            Some(assign) => record.key_value(assign),
+            None => record.pair("two words"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn texts<'a>(segments: impl IntoIterator<Item = Segment<'a>>) -> Vec<&'a str> {
+        segments.into_iter().map(|s| s.as_str()).collect()
+    }
+
+    #[test]
+    fn reads_labelled_prefixes() {
+        let line = Segment::new("Card  12:  1 2 | 3");
+        let (id, rest) = line.labelled("Card", ':').unwrap();
+        assert_eq!(id.parse::<u32>("an id"), Ok(12));
+        assert_eq!(id.span(), Span::new(6, 8));
+        assert_eq!(rest.as_str(), "  1 2 | 3");
+
+        let error = Segment::new("  Gme 12: 3 blue")
+            .labelled("Game", ':')
+            .unwrap_err();
+        assert_eq!(error.found, "Gme 12");
+        assert_eq!(error.span, Span::new(2, 8));
+
+        let error = Segment::new("Game 1 3 blue")
+            .labelled("Game", ':')
+            .unwrap_err();
+        assert_eq!(error.expected, "`:`");
+        assert_eq!(error.span, Span::new(0, 13));
+    }
+
+    #[test]
+    fn splits_lists_keeping_the_spans() {
+        let line = Segment::new("a: 1 red, 2 green; 3 blue");
+        let (_, rounds) = line.split_once(':').unwrap();
+        let rounds = rounds.split(';').collect::<Vec<Segment>>();
+        assert_eq!(texts(rounds.clone()), ["1 red, 2 green", "3 blue"]);
+        assert_eq!(rounds[1].span(), Span::new(19, 25));
+
+        let entries = rounds[0].list(',', |entry| entry.pair("<count> <color>"));
+        let entries = entries.unwrap();
+        assert_eq!(entries[1].1.as_str(), "green");
+        assert_eq!(entries[1].1.span(), Span::new(12, 17));
+    }
+
+    #[test]
+    fn reads_integers_with_any_spacing() {
+        let line = Segment::new(" 83 86  6\t31 ");
+        assert_eq!(line.integers::<u8>(), Ok(vec![83, 86, 6, 31]));
+
+        let error = Segment::new("41 4x 83").integers::<u32>().unwrap_err();
+        assert_eq!(error.to_string(), "Expected an integer, found \"4x\"");
+        assert_eq!(error.span(), Some(Span::new(3, 5)));
+
+        let (_, numbers) = Segment::new("x: -3 7").split_once(':').unwrap();
+        assert_eq!(numbers.integers::<i8>(), Ok(vec![-3, 7]));
+        let error = numbers.integers::<u8>().unwrap_err();
+        assert_eq!(error.span(), Some(Span::new(3, 5)));
+        let (_, numbers) = Segment::new("x: 7 300").split_once(':').unwrap();
+        let error = numbers.integers::<u8>().unwrap_err();
+        assert_eq!(error.span(), Some(Span::new(5, 8)));
+        for text in ["3-5", "12ab", "a 1", "1 +2"] {
+            assert!(Segment::new(text).integers::<i32>().is_err(), "{}", text);
+        }
+    }
+
+    #[test]
+    fn reads_records() {
+        let records = Segment::new("red=3, blue = 4").records(',', Some('='));
+        let records = records
+            .unwrap()
+            .into_iter()
+            .map(|(key, value)| (key.as_str(), value.as_str()))
+            .collect::<Vec<(&str, &str)>>();
+        assert_eq!(records, [("red", "3"), ("blue", "4")]);
+
+        let error = Segment::new("1 red, 2").records(',', None).unwrap_err();
+        assert_eq!(error.found, "2");
+        assert_eq!(error.span, Span::new(7, 8));
+    }
+}
@@ -0,0 +1,263 @@
+use std::{cmp::Reverse, collections::VecDeque};
+
+use crate::diagnostic::Span;
+
+/// State of the automaton before reading anything
+const START: usize = 0;
+
+/// Occurrence of a pattern in a text
+#[derive(Debug, PartialEq, Eq)]
+pub struct Match<'a, V> {
+    /// Value given with the pattern
+    pub value: &'a V,
+    pub span: Span,
+}
+
+// Not derived, which would require the values to be `Copy`
+impl<V> Clone for Match<'_, V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V> Copy for Match<'_, V> {}
+
+/// Set of byte patterns, each with a value, searched all at once with an
+/// Aho-Corasick automaton: every byte of the text is read once, whatever the
+/// number of patterns.
+#[derive(Debug, Clone)]
+pub struct PatternSet<V> {
+    /// Next state for each state and byte
+    transitions: Vec<[usize; 256]>,
+    /// Patterns ending at each state, including through its suffixes
+    outputs: Vec<Vec<usize>>,
+    /// Length and value of each pattern
+    patterns: Vec<(usize, V)>,
+    /// Length of the longest pattern
+    max_len: usize,
+}
+
+impl<V> PatternSet<V> {
+    /// Builds the automaton of the patterns. Empty patterns never match.
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = (P, V)>) -> Self {
+        let mut set = Self {
+            transitions: vec![[START; 256]],
+            outputs: vec![Vec::new()],
+            patterns: Vec::new(),
+            max_len: 0,
+        };
+
+        // Trie of the patterns, where a missing transition goes back to the start
+        for (pattern, value) in patterns {
+            let pattern = pattern.as_ref();
+            if pattern.is_empty() {
+                continue;
+            }
+            let mut state = START;
+            for &byte in pattern {
+                if set.transitions[state][byte as usize] == START {
+                    set.transitions.push([START; 256]);
+                    set.outputs.push(Vec::new());
+                    set.transitions[state][byte as usize] = set.transitions.len() - 1;
+                }
+                state = set.transitions[state][byte as usize];
+            }
+            set.outputs[state].push(set.patterns.len());
+            set.patterns.push((pattern.len(), value));
+            set.max_len = set.max_len.max(pattern.len());
+        }
+
+        // Breadth first, so that the state of the longest proper suffix of
+        // each state (its fallback) is complete before the state itself
+        let mut fallbacks = vec![START; set.transitions.len()];
+        let mut queue = set.transitions[START]
+            .iter()
+            .copied()
+            .filter(|next| *next != START)
+            .collect::<VecDeque<usize>>();
+        while let Some(state) = queue.pop_front() {
+            let fallback = fallbacks[state];
+            let inherited = set.outputs[fallback].clone();
+            set.outputs[state].extend(inherited);
+            for byte in 0..256 {
+                let next = set.transitions[state][byte];
+                if next == START {
+                    set.transitions[state][byte] = set.transitions[fallback][byte];
+                } else {
+                    fallbacks[next] = set.transitions[fallback][byte];
+                    queue.push_back(next);
+                }
+            }
+        }
+        set
+    }
+
+    /// Number of non-empty patterns
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+
+    /// Length of the longest pattern
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Every occurrence of the patterns, overlapping ones included, ordered by
+    /// where they end
+    pub fn find_iter<'a>(
+        &'a self,
+        text: &'a (impl AsRef<[u8]> + ?Sized),
+    ) -> impl Iterator<Item = Match<'a, V>> + 'a {
+        let mut state = START;
+        text.as_ref()
+            .iter()
+            .enumerate()
+            .flat_map(move |(index, &byte)| {
+                state = self.transitions[state][byte as usize];
+                self.outputs[state].iter().map(move |&pattern| {
+                    let (len, value) = &self.patterns[pattern];
+                    Match {
+                        value,
+                        span: Span::new(index + 1 - len, index + 1),
+                    }
+                })
+            })
+    }
+
+    /// Occurrences starting first and ending last, found in a single pass,
+    /// like searching forwards and backwards. When several occurrences start
+    /// (or end) at the same place, the longest one is kept.
+    pub fn first_last<'a>(
+        &'a self,
+        text: &'a (impl AsRef<[u8]> + ?Sized),
+    ) -> Option<(Match<'a, V>, Match<'a, V>)> {
+        let mut found = self.find_iter(text);
+        let first = found.next()?;
+        Some(found.fold((first, first), |(first, last), found| {
+            let earlier = (found.span.start, Reverse(found.span.len()))
+                < (first.span.start, Reverse(first.span.len()));
+            let later = (found.span.end, found.span.len()) > (last.span.end, last.span.len());
+            (
+                if earlier { found } else { first },
+                if later { found } else { last },
+            )
+        }))
+    }
+
+    /// Occurrence starting first in a stream of bytes, the longest when several
+    /// start at the same place. Reading stops as soon as no other occurrence
+    /// can start earlier, so a reversed stream finds the occurrence ending last
+    /// of the reversed patterns without reading the whole text.
+    pub fn first_in(&self, bytes: impl IntoIterator<Item = u8>) -> Option<Match<'_, V>> {
+        let mut state = START;
+        let mut first: Option<Match<V>> = None;
+        for (index, byte) in bytes.into_iter().enumerate() {
+            if first.is_some_and(|first| index >= first.span.start + self.max_len) {
+                break;
+            }
+            state = self.transitions[state][byte as usize];
+            for &pattern in &self.outputs[state] {
+                let (len, value) = &self.patterns[pattern];
+                let found = Match {
+                    value,
+                    span: Span::new(index + 1 - len, index + 1),
+                };
+                if first.is_none_or(|first| {
+                    (found.span.start, Reverse(found.span.len()))
+                        < (first.span.start, Reverse(first.span.len()))
+                }) {
+                    first = Some(found);
+                }
+            }
+        }
+        first
+    }
+
+    /// Occurrence starting first
+    pub fn first<'a>(&'a self, text: &'a (impl AsRef<[u8]> + ?Sized)) -> Option<Match<'a, V>> {
+        self.first_last(text).map(|(first, _)| first)
+    }
+
+    /// Occurrence ending last
+    pub fn last<'a>(&'a self, text: &'a (impl AsRef<[u8]> + ?Sized)) -> Option<Match<'a, V>> {
+        self.first_last(text).map(|(_, last)| last)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values<'a>(matches: impl Iterator<Item = Match<'a, &'a str>>) -> Vec<&'a str> {
+        matches.map(|found| *found.value).collect()
+    }
+
+    #[test]
+    fn finds_overlapping_occurrences() {
+        let set = PatternSet::new([
+            ("he", "he"),
+            ("she", "she"),
+            ("his", "his"),
+            ("hers", "hers"),
+        ]);
+        assert_eq!(set.len(), 4);
+        assert_eq!(values(set.find_iter("ushers")), ["she", "he", "hers"]);
+        let spans = set
+            .find_iter("ushers")
+            .map(|found| found.span)
+            .collect::<Vec<Span>>();
+        assert_eq!(spans, [Span::new(1, 4), Span::new(2, 4), Span::new(2, 6)]);
+        assert_eq!(values(set.find_iter("ahishe")), ["his", "she", "he"]);
+    }
+
+    #[test]
+    fn finds_the_first_and_last_occurrences() {
+        let set = PatternSet::new([("one", 1), ("eight", 8), ("two", 2), ("1", 1), ("8", 8)]);
+        let (first, last) = set.first_last("xoneightwo8").unwrap();
+        assert_eq!((first.value, first.span), (&1, Span::new(1, 4)));
+        assert_eq!((last.value, last.span), (&8, Span::new(10, 11)));
+        assert_eq!(set.first("eightwo").map(|found| *found.value), Some(8));
+        assert_eq!(set.last("eightwo").map(|found| *found.value), Some(2));
+        assert_eq!(set.first_last("nothing here"), None);
+    }
+
+    #[test]
+    fn prefers_the_longest_occurrence_at_a_position() {
+        let set = PatternSet::new([("abcd", "abcd"), ("bc", "bc"), ("ab", "ab"), ("d", "d")]);
+        let (first, last) = set.first_last("abcd").unwrap();
+        assert_eq!((*first.value, *last.value), ("abcd", "abcd"));
+        let (first, last) = set.first_last("abcabc").unwrap();
+        assert_eq!((*first.value, *last.value), ("ab", "bc"));
+    }
+
+    #[test]
+    fn stops_reading_once_the_first_occurrence_is_known() {
+        let set = PatternSet::new([("abcd", "abcd"), ("bc", "bc"), ("c", "c")]);
+        assert_eq!(set.max_len(), 4);
+        let mut read = 0;
+        let bytes = b"xabcdcccccc".iter().inspect(|_| read += 1).copied();
+        let first = set.first_in(bytes).unwrap();
+        assert_eq!((*first.value, first.span), ("abcd", Span::new(1, 5)));
+        assert_eq!(read, 6);
+
+        let reversed = PatternSet::new([("owt", 2), ("thgie", 8)]);
+        let last = reversed.first_in(b"eightwo".iter().rev().copied());
+        assert_eq!(
+            last.map(|found| (*found.value, found.span)),
+            Some((2, Span::new(0, 3)))
+        );
+        assert_eq!(set.first_in(*b"xyz"), None);
+    }
+
+    #[test]
+    fn ignores_empty_patterns() {
+        let set = PatternSet::new([("", 0), ("a", 1)]);
+        assert_eq!(set.len(), 1);
+        assert_eq!(set.find_iter(b"aba").count(), 2);
+        assert!(PatternSet::<u8>::new(Vec::<(&str, u8)>::new()).is_empty());
+    }
+}
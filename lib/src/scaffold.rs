@@ -0,0 +1,106 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use crate::error::{Error, Result};
+
+/// Files of a new day crate, relative to its directory, and their templates.
+/// `{{day}}` is replaced by the day number.
+const TEMPLATES: [(&str, &str); 8] = [
+    (
+        "Cargo.toml",
+        include_str!("../templates/day/Cargo.toml.tmpl"),
+    ),
+    (
+        "answers.toml",
+        include_str!("../templates/day/answers.toml.tmpl"),
+    ),
+    ("inputs/test.txt", ""),
+    ("src/lib.rs", include_str!("../templates/day/lib.rs.tmpl")),
+    (
+        "src/solver.rs",
+        include_str!("../templates/day/solver.rs.tmpl"),
+    ),
+    (
+        "src/bin/part-1.rs",
+        include_str!("../templates/day/part-1.rs.tmpl"),
+    ),
+    (
+        "src/bin/part-2.rs",
+        include_str!("../templates/day/part-2.rs.tmpl"),
+    ),
+    (
+        "tests/fixtures.rs",
+        include_str!("../templates/day/fixtures.rs.tmpl"),
+    ),
+];
+
+/// Directory of a day's crate in the workspace
+pub fn day_dir(root: &Path, day: u8) -> PathBuf {
+    root.join(format!("day-{}", day))
+}
+
+/// Creates the `day-N` crate in the workspace at `root`, returning the created
+/// files. Fails without writing anything when the crate already exists.
+pub fn scaffold(root: &Path, day: u8) -> Result<Vec<PathBuf>> {
+    let dir = day_dir(root, day);
+    if dir.exists() {
+        return Err(scaffold_error(
+            &dir,
+            io::Error::new(io::ErrorKind::AlreadyExists, "the day already exists"),
+        ));
+    }
+
+    let mut created = Vec::with_capacity(TEMPLATES.len());
+    for (file, template) in TEMPLATES {
+        let path = dir.join(file);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| scaffold_error(parent, e))?;
+        }
+        fs::write(&path, render(template, day)).map_err(|e| scaffold_error(&path, e))?;
+        created.push(path);
+    }
+    Ok(created)
+}
+
+fn render(template: &str, day: u8) -> String {
+    template.replace("{{day}}", &day.to_string())
+}
+
+fn scaffold_error(path: &Path, source: io::Error) -> Error {
+    Error::Scaffold {
+        path: path.display().to_string(),
+        source,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    #[test]
+    fn renders_the_day() {
+        let solver = render(TEMPLATES[4].1, 12);
+        assert!(solver.contains("pub struct Day12;"));
+        assert!(solver.contains("const DAY: u8 = 12;"));
+        assert!(!solver.contains("{{day}}"));
+    }
+
+    #[test]
+    fn creates_the_crate_once() {
+        let root = env::temp_dir().join(format!("aoc-scaffold-{}", std::process::id()));
+        let created = scaffold(&root, 7).unwrap();
+        assert_eq!(created.len(), TEMPLATES.len());
+
+        let manifest = fs::read_to_string(root.join("day-7/Cargo.toml")).unwrap();
+        assert!(manifest.contains("name = \"day-7\""));
+        let binary = fs::read_to_string(root.join("day-7/src/bin/part-2.rs")).unwrap();
+        assert!(binary.contains("run_part(&Day7, Part::Two)"));
+
+        assert!(matches!(scaffold(&root, 7), Err(Error::Scaffold { .. })));
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
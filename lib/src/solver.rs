@@ -0,0 +1,606 @@
+use std::{
+    env, fmt,
+    io::BufRead,
+    path::{Path, PathBuf},
+    process::ExitCode,
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    answers::{input_key, Answers, Verdict, ANSWERS_FILE},
+    bench::{measure, BenchOptions, BenchReport, Phase},
+    client::Client,
+    error::{Error, Result},
+    input::{find_input, Input, InputNotFound, LineReader, LineSource, INPUT_FILE_NAMES},
+    submit::{now, History, Outcome, SUBMISSIONS_FILE},
+};
+
+/// One of the two parts of a day's puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    One,
+    Two,
+}
+
+impl Part {
+    pub const ALL: [Part; 2] = [Part::One, Part::Two];
+}
+
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Part::One => write!(f, "1"),
+            Part::Two => write!(f, "2"),
+        }
+    }
+}
+
+impl FromStr for Part {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim() {
+            "1" => Ok(Part::One),
+            "2" => Ok(Part::Two),
+            other => Err(format!("Invalid part: {} (expected 1 or 2)", other)),
+        }
+    }
+}
+
+/// The answer to a puzzle part
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum Answer {
+    Number(u64),
+    Text(String),
+}
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Answer::Number(number) => write!(f, "{}", number),
+            Answer::Text(text) => write!(f, "{}", text),
+        }
+    }
+}
+
+macro_rules! answer_from_number {
+    ($($number:ty),*) => {
+        $(
+            impl From<$number> for Answer {
+                fn from(number: $number) -> Self {
+                    Answer::Number(number as u64)
+                }
+            }
+        )*
+    };
+}
+
+answer_from_number!(u8, u16, u32, u64, usize);
+
+impl FromStr for Answer {
+    type Err = std::convert::Infallible;
+
+    /// Numbers are read as [`Answer::Number`], anything else as text
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        Ok(s.parse::<u64>()
+            .map(Answer::Number)
+            .unwrap_or_else(|_| Answer::Text(s.to_string())))
+    }
+}
+
+impl From<String> for Answer {
+    fn from(text: String) -> Self {
+        Answer::Text(text)
+    }
+}
+
+impl From<&str> for Answer {
+    fn from(text: &str) -> Self {
+        Answer::Text(text.to_string())
+    }
+}
+
+/// A day's puzzle: the input is parsed once and shared by both parts
+pub trait Solution {
+    /// Parsed puzzle input
+    type Input;
+    type PartOne: Into<Answer>;
+    type PartTwo: Into<Answer>;
+
+    /// Day of the puzzle
+    const DAY: u8;
+    /// Directory with the day's input files, usually `concat!(env!("CARGO_MANIFEST_DIR"), "/inputs")`
+    const INPUTS: &'static str;
+
+    /// Parses the input, reading it line by line
+    fn parse(&self, lines: &mut dyn LineSource) -> Result<Self::Input>;
+
+    /// Parses the raw bytes of the input, line by line with [`Solution::parse`]
+    /// unless overridden
+    fn parse_reader(&self, reader: &mut dyn BufRead) -> Result<Self::Input> {
+        self.parse(&mut LineReader::new(reader))
+    }
+
+    fn part_one(&self, input: &Self::Input) -> Result<Self::PartOne>;
+
+    fn part_two(&self, input: &Self::Input) -> Result<Self::PartTwo>;
+}
+
+/// Object safe view of a [`Solution`], so solutions of different days can be
+/// stored together in a [`Registry`]
+pub trait Solver {
+    fn day(&self) -> u8;
+
+    /// Directory with the day's input files
+    fn inputs(&self) -> &'static str;
+
+    /// Finds the input to use, see [`find_input`]
+    fn find_input(&self, arg: Option<&str>) -> Result<Input, InputNotFound> {
+        find_input(self.day(), arg, Path::new(self.inputs()))
+    }
+
+    /// Finds the input to use, downloading it into the day's inputs directory
+    /// when there is none
+    fn find_or_fetch_input(&self, arg: Option<&str>, client: &Client) -> Result<Input> {
+        let not_found = match self.find_input(arg) {
+            Ok(input) => return Ok(input),
+            Err(not_found) => not_found,
+        };
+        let path = Path::new(self.inputs()).join(INPUT_FILE_NAMES[0]);
+        match client.fetch_input(self.day(), &path) {
+            Ok(()) => Ok(Input::File(path)),
+            Err(source) => Err(Error::Fetch { not_found, source }),
+        }
+    }
+
+    /// Directory of the day's crate, the parent of the inputs directory
+    fn crate_dir(&self) -> &'static Path {
+        let inputs = Path::new(self.inputs());
+        inputs.parent().unwrap_or(inputs)
+    }
+
+    /// File with the known answers of the day
+    fn answers_path(&self) -> PathBuf {
+        self.crate_dir().join(ANSWERS_FILE)
+    }
+
+    /// File with the answers submitted to the website
+    fn submissions_path(&self) -> PathBuf {
+        self.crate_dir().join(SUBMISSIONS_FILE)
+    }
+
+    /// Parses the input once and solves each of the given parts
+    fn solve(&self, reader: &mut dyn BufRead, parts: &[Part]) -> Result<Vec<Answer>>;
+
+    /// Times the parsing of the text and each of the given parts separately
+    fn bench(
+        &self,
+        input: &Input,
+        text: &str,
+        parts: &[Part],
+        options: &BenchOptions,
+    ) -> Result<BenchReport>;
+}
+
+impl<S: Solution> Solver for S {
+    fn day(&self) -> u8 {
+        S::DAY
+    }
+
+    fn inputs(&self) -> &'static str {
+        S::INPUTS
+    }
+
+    fn solve(&self, reader: &mut dyn BufRead, parts: &[Part]) -> Result<Vec<Answer>> {
+        let input = self.parse_reader(reader)?;
+        parts
+            .iter()
+            .map(|part| match part {
+                Part::One => self.part_one(&input).map(Into::into),
+                Part::Two => self.part_two(&input).map(Into::into),
+            })
+            .collect()
+    }
+
+    fn bench(
+        &self,
+        input: &Input,
+        text: &str,
+        parts: &[Part],
+        options: &BenchOptions,
+    ) -> Result<BenchReport> {
+        let mut report = BenchReport::new(S::DAY, input.to_string(), text, options);
+        let samples = measure(options, || self.parse_reader(&mut text.as_bytes()))?;
+        report.add(Phase::Parse, &samples);
+
+        let parsed = self.parse_reader(&mut text.as_bytes())?;
+        for part in parts {
+            let samples = match part {
+                Part::One => measure(options, || self.part_one(&parsed))?,
+                Part::Two => measure(options, || self.part_two(&parsed))?,
+            };
+            report.add(Phase::from(*part), &samples);
+        }
+        Ok(report)
+    }
+}
+
+/// Collection of the solutions of every day, looked up by day number
+#[derive(Default)]
+pub struct Registry {
+    solvers: Vec<Box<dyn Solver>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a solution to the registry, replacing any solution registered for the same day
+    pub fn register<S: Solution + 'static>(&mut self, solution: S) -> &mut Self {
+        self.solvers.retain(|solver| solver.day() != S::DAY);
+        self.solvers.push(Box::new(solution));
+        self.solvers.sort_by_key(|solver| solver.day());
+        self
+    }
+
+    pub fn get(&self, day: u8) -> Option<&dyn Solver> {
+        self.solvers
+            .iter()
+            .find(|solver| solver.day() == day)
+            .map(|solver| solver.as_ref())
+    }
+
+    /// Registered solvers, in ascending day order
+    pub fn solvers(&self) -> impl Iterator<Item = &dyn Solver> {
+        self.solvers.iter().map(|solver| solver.as_ref())
+    }
+}
+
+/// Answer to a part, checked against the recorded answers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solved {
+    pub part: Part,
+    pub answer: Answer,
+    pub verdict: Verdict,
+}
+
+/// Reads the input, solves the given parts and prints their answers along
+/// with how they compare to the recorded ones
+pub fn run(solver: &dyn Solver, parts: &[Part], input: &Input) -> Result<Vec<Solved>> {
+    let answers = Answers::load_dir(solver.crate_dir())?;
+    let key = input_key(input, Path::new(solver.inputs()));
+    let solved = solve(solver, parts, input, &answers, key.as_deref())?;
+
+    for solved in solved.iter() {
+        println!(
+            "Day {} part {}: {} ({})",
+            solver.day(),
+            solved.part,
+            solved.answer,
+            solved.verdict
+        );
+    }
+    Ok(solved)
+}
+
+/// Opens the input to read its raw bytes
+fn open(input: &Input) -> Result<Box<dyn BufRead>> {
+    input.reader().map_err(|source| Error::Open {
+        input: input.to_string(),
+        source,
+    })
+}
+
+fn solve(
+    solver: &dyn Solver,
+    parts: &[Part],
+    input: &Input,
+    answers: &Answers,
+    key: Option<&str>,
+) -> Result<Vec<Solved>> {
+    let mut reader = open(input)?;
+
+    let solved = solver.solve(reader.as_mut(), parts)?;
+    Ok(parts
+        .iter()
+        .zip(solved)
+        .map(|(part, answer)| Solved {
+            part: *part,
+            verdict: key.map_or(Verdict::Unknown, |key| answers.check(key, *part, &answer)),
+            answer,
+        })
+        .collect())
+}
+
+/// Saves the answers into the answers file of the day
+pub fn record(solver: &dyn Solver, input: &str, solved: &[Solved]) -> Result<()> {
+    let path = solver.answers_path();
+    let mut answers = Answers::load(&path)?;
+    for solved in solved {
+        answers.record(input, solved.part, solved.answer.clone());
+    }
+    answers.save(&path)
+}
+
+/// Solves every input that has recorded answers and prints how they compare.
+/// Returns whether all of them passed.
+pub fn verify(solver: &dyn Solver) -> Result<bool> {
+    let answers = Answers::load_dir(solver.crate_dir())?;
+    let mut passed = true;
+
+    for key in answers.inputs() {
+        let parts = Part::ALL
+            .into_iter()
+            .filter(|part| answers.get(key, *part).is_some())
+            .collect::<Vec<Part>>();
+        let input = Input::File(Path::new(solver.inputs()).join(key));
+
+        for solved in solve(solver, &parts, &input, &answers, Some(key))? {
+            println!(
+                "Day {} part {} {}: {} ({})",
+                solver.day(),
+                solved.part,
+                key,
+                solved.answer,
+                solved.verdict
+            );
+            passed &= solved.verdict == Verdict::Pass;
+        }
+    }
+    Ok(passed)
+}
+
+/// Submits the answer of a part unless the submission history shows it cannot
+/// be right, then remembers the response. Correct answers of a file input are
+/// also saved as its known answer.
+pub fn submit(
+    solver: &dyn Solver,
+    client: &Client,
+    part: Part,
+    answer: Answer,
+    input: Option<&Input>,
+) -> Result<Outcome> {
+    let path = solver.submissions_path();
+    let mut history = History::load(&path)?;
+    history.check(part, &answer, now())?;
+
+    let outcome = client.submit(solver.day(), part, &answer)?;
+    history.record(part, answer.clone(), outcome.clone(), now());
+    history.save(&path)?;
+
+    if let (Outcome::Correct, Some(key)) = (
+        &outcome,
+        input.and_then(|input| input_key(input, Path::new(solver.inputs()))),
+    ) {
+        let solved = Solved {
+            part,
+            answer,
+            verdict: Verdict::Pass,
+        };
+        record(solver, &key, &[solved])?;
+    }
+    Ok(outcome)
+}
+
+/// Reads the whole input and benchmarks the solver on it
+pub fn bench(
+    solver: &dyn Solver,
+    parts: &[Part],
+    input: &Input,
+    options: &BenchOptions,
+) -> Result<BenchReport> {
+    let text = input.read_to_string().map_err(|source| Error::Open {
+        input: input.to_string(),
+        source,
+    })?;
+    solver.bench(input, &text, parts, options)
+}
+
+/// Finds the input like [`run_part`] and parses it
+pub fn load_input<S: Solution>(solution: &S, arg: Option<&str>) -> Result<S::Input> {
+    let input = solution.find_or_fetch_input(arg, &Client::from_env())?;
+    solution.parse_reader(open(&input)?.as_mut())
+}
+
+/// Entry point of the part binaries. The input can be given as the first
+/// argument (`-` for stdin), otherwise it is looked up with [`find_input`].
+pub fn run_part(solver: &dyn Solver, part: Part) -> ExitCode {
+    let arg = env::args().nth(1);
+    exit_code(solver.day(), solve_part(solver, part, arg.as_deref()))
+}
+
+/// How a part binary prints a report on its input, see [`run_part_with`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReportFormat {
+    Text,
+    Json,
+}
+
+/// Entry point of the part binaries that can explain their answer. With
+/// `--report` the report built from the parsed input is printed instead of
+/// the answer, with `--json` it is printed as JSON. The input is given like
+/// for [`run_part`].
+pub fn run_part_with<S, R>(
+    solution: &S,
+    part: Part,
+    report: impl FnOnce(&S::Input) -> R,
+) -> ExitCode
+where
+    S: Solution,
+    R: fmt::Display + Serialize,
+{
+    let (format, arg) = match part_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(usage) => {
+            eprintln!("{}", usage);
+            return ExitCode::FAILURE;
+        }
+    };
+    let Some(format) = format else {
+        return exit_code(S::DAY, solve_part(solution, part, arg.as_deref()));
+    };
+
+    let report = match load_input(solution, arg.as_deref()) {
+        Ok(input) => report(&input),
+        Err(e) => return exit_code(S::DAY, Err(e)),
+    };
+    match format {
+        ReportFormat::Text => println!("{}", report),
+        ReportFormat::Json => match serde_json::to_string_pretty(&report) {
+            Ok(json) => println!("{}", json),
+            Err(e) => {
+                eprintln!("error: {}", e);
+                return ExitCode::FAILURE;
+            }
+        },
+    }
+    ExitCode::SUCCESS
+}
+
+/// Report format and input of a part binary, from its arguments
+fn part_args(
+    args: impl IntoIterator<Item = String>,
+) -> Result<(Option<ReportFormat>, Option<String>), String> {
+    let mut format = None;
+    let mut input = None;
+    for arg in args {
+        match arg.as_str() {
+            "--report" => format = format.or(Some(ReportFormat::Text)),
+            "--json" => format = Some(ReportFormat::Json),
+            _ if arg.starts_with("--") || input.is_some() => {
+                return Err("usage: [--report | --json] [input]".to_string())
+            }
+            _ => input = Some(arg),
+        }
+    }
+    Ok((format, input))
+}
+
+fn solve_part(solver: &dyn Solver, part: Part, arg: Option<&str>) -> Result<()> {
+    let input = solver.find_or_fetch_input(arg, &Client::from_env())?;
+    run(solver, &[part], &input).map(|_| ())
+}
+
+fn exit_code(day: u8, result: Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: day {}: {}", day, e.report());
+            ExitCode::FAILURE
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct LineCount<const DAY: u8>;
+
+    impl<const DAY: u8> Solution for LineCount<DAY> {
+        type Input = usize;
+        type PartOne = usize;
+        type PartTwo = String;
+
+        const DAY: u8 = DAY;
+        const INPUTS: &'static str = "inputs";
+
+        fn parse(&self, lines: &mut dyn LineSource) -> Result<Self::Input> {
+            let mut count = 0;
+            while lines.next_line()?.is_some() {
+                count += 1;
+            }
+            Ok(count)
+        }
+
+        fn part_one(&self, input: &Self::Input) -> Result<Self::PartOne> {
+            Ok(*input)
+        }
+
+        fn part_two(&self, input: &Self::Input) -> Result<Self::PartTwo> {
+            Ok(format!("{} lines", input))
+        }
+    }
+
+    #[test]
+    fn parses_answers() {
+        assert_eq!("42".parse::<Answer>(), Ok(Answer::Number(42)));
+        assert_eq!(
+            " abc \n".parse::<Answer>(),
+            Ok(Answer::Text("abc".to_string()))
+        );
+    }
+
+    #[test]
+    fn parses_parts() {
+        assert_eq!("1".parse::<Part>(), Ok(Part::One));
+        assert_eq!("2".parse::<Part>(), Ok(Part::Two));
+        assert!("3".parse::<Part>().is_err());
+    }
+
+    #[test]
+    fn parses_part_args() {
+        let args = |args: &[&str]| part_args(args.iter().map(|arg| arg.to_string()));
+        assert_eq!(args(&[]), Ok((None, None)));
+        assert_eq!(args(&["-"]), Ok((None, Some("-".to_string()))));
+        assert_eq!(
+            args(&["--report", "test.txt"]),
+            Ok((Some(ReportFormat::Text), Some("test.txt".to_string())))
+        );
+        assert_eq!(
+            args(&["--json", "--report"]),
+            Ok((Some(ReportFormat::Json), None))
+        );
+        assert!(args(&["--bogus"]).is_err());
+        assert!(args(&["a.txt", "b.txt"]).is_err());
+    }
+
+    #[test]
+    fn registers_solutions_in_order() {
+        let mut registry = Registry::new();
+        registry
+            .register(LineCount::<3>)
+            .register(LineCount::<1>)
+            .register(LineCount::<2>);
+
+        let days = registry.solvers().map(|s| s.day()).collect::<Vec<u8>>();
+        assert_eq!(days, vec![1, 2, 3]);
+        assert!(registry.get(2).is_some());
+        assert!(registry.get(4).is_none());
+    }
+
+    #[test]
+    fn benches_each_phase() {
+        let options = BenchOptions {
+            iterations: 3,
+            warmup: 0,
+        };
+        let input = Input::File("test.txt".into());
+        let report = LineCount::<1>
+            .bench(&input, "a\nb\n", &[Part::Two], &options)
+            .unwrap();
+        let phases = report
+            .phases
+            .iter()
+            .map(|phase| phase.phase)
+            .collect::<Vec<Phase>>();
+        assert_eq!(phases, vec![Phase::Parse, Phase::PartTwo]);
+        assert_eq!(report.lines, 2);
+    }
+
+    #[test]
+    fn solves_requested_parts() {
+        let answers = LineCount::<1>
+            .solve(&mut "a\nb".as_bytes(), &Part::ALL)
+            .unwrap();
+        assert_eq!(
+            answers,
+            vec![Answer::Number(2), Answer::Text("2 lines".to_string())]
+        );
+    }
+}
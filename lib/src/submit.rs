@@ -0,0 +1,387 @@
+use std::{
+    fmt, fs, io,
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::{
+    error::{Error, Result},
+    examples::text,
+    solver::{Answer, Part},
+};
+
+/// Name of the submission history of each day, next to its `inputs/` directory
+pub const SUBMISSIONS_FILE: &str = "submissions.toml";
+/// Time the website makes you wait after a wrong answer
+const WRONG_ANSWER_WAIT: u64 = 60;
+
+/// Response of the website to a submitted answer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without saying in which direction
+    Wrong,
+    /// An answer was submitted too recently
+    Wait {
+        seconds: u64,
+    },
+    /// The part was already solved, or is not unlocked yet
+    WrongLevel,
+    /// Response that could not be understood, with its text
+    Unknown(String),
+}
+
+impl Outcome {
+    /// Reads the outcome from the HTML page returned by the website
+    pub fn parse(html: &str) -> Self {
+        let text = message(html);
+        let lower = text.to_lowercase();
+        if lower.contains("that's the right answer") {
+            Outcome::Correct
+        } else if lower.contains("your answer is too high") {
+            Outcome::TooHigh
+        } else if lower.contains("your answer is too low") {
+            Outcome::TooLow
+        } else if lower.contains("that's not the right answer") {
+            Outcome::Wrong
+        } else if lower.contains("you gave an answer too recently") {
+            Outcome::Wait {
+                seconds: wait_seconds(&lower).unwrap_or(WRONG_ANSWER_WAIT),
+            }
+        } else if lower.contains("you don't seem to be solving the right level") {
+            Outcome::WrongLevel
+        } else {
+            Outcome::Unknown(text)
+        }
+    }
+
+    /// Whether the answer was judged, so it is worth remembering
+    fn is_verdict(&self) -> bool {
+        matches!(
+            self,
+            Outcome::Correct | Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong
+        )
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Outcome::Correct => write!(f, "correct"),
+            Outcome::TooHigh => write!(f, "wrong, too high"),
+            Outcome::TooLow => write!(f, "wrong, too low"),
+            Outcome::Wrong => write!(f, "wrong"),
+            Outcome::Wait { seconds } => write!(f, "submitted too recently, wait {}s", seconds),
+            Outcome::WrongLevel => write!(f, "the part is already solved or still locked"),
+            Outcome::Unknown(text) => write!(f, "unknown response: {}", text),
+        }
+    }
+}
+
+/// Text of the `<article>` of the page, without tags
+fn message(html: &str) -> String {
+    let article = html
+        .split_once("<article")
+        .and_then(|(_, rest)| rest.split_once("</article>"))
+        .map(|(article, _)| article)
+        .unwrap_or(html);
+
+    let text = text(article);
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+/// Reads "you have 1m 23s left to wait"
+fn wait_seconds(text: &str) -> Option<u64> {
+    let (_, rest) = text.split_once("you have ")?;
+    let (wait, _) = rest.split_once(" left to wait")?;
+    wait.split_whitespace().try_fold(0, |total, amount| {
+        let (number, unit) = amount.split_at(amount.find(|c: char| !c.is_ascii_digit())?);
+        let number = number.parse::<u64>().ok()?;
+        let seconds = match unit {
+            "h" => number * 3600,
+            "m" => number * 60,
+            "s" => number,
+            _ => return None,
+        };
+        Some(total + seconds)
+    })
+}
+
+/// Reason to not submit an answer
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Refusal {
+    #[error("part {part} is already solved, the answer was {answer}")]
+    Solved { part: Part, answer: Answer },
+
+    #[error("{answer} was already submitted and is wrong")]
+    KnownWrong { answer: Answer },
+
+    #[error("{answer} is too high, {bound} already was")]
+    TooHigh { answer: Answer, bound: u64 },
+
+    #[error("{answer} is too low, {bound} already was")]
+    TooLow { answer: Answer, bound: u64 },
+
+    #[error("the website asked to wait, try again in {seconds}s")]
+    Wait { seconds: u64 },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Submission {
+    pub answer: Answer,
+    pub outcome: Outcome,
+    /// Seconds since the Unix epoch
+    pub at: u64,
+}
+
+/// Answers submitted for a day and how the website judged them
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct History {
+    /// Seconds since the Unix epoch until which submitting is blocked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blocked_until: Option<u64>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub part_1: Vec<Submission>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub part_2: Vec<Submission>,
+}
+
+impl History {
+    /// Reads a history file, a missing file is an empty history
+    pub fn load(path: &Path) -> Result<Self> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => return Err(history_error(path, e)),
+        };
+        toml::from_str(&text).map_err(|e| history_error(path, e))
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = toml::to_string(self).map_err(|e| history_error(path, e))?;
+        fs::write(path, text).map_err(|e| history_error(path, e))
+    }
+
+    pub fn submissions(&self, part: Part) -> &[Submission] {
+        match part {
+            Part::One => &self.part_1,
+            Part::Two => &self.part_2,
+        }
+    }
+
+    /// Checks that submitting the answer at the given time can be useful
+    pub fn check(&self, part: Part, answer: &Answer, now: u64) -> Result<(), Refusal> {
+        if let Some(until) = self.blocked_until.filter(|until| *until > now) {
+            return Err(Refusal::Wait {
+                seconds: until - now,
+            });
+        }
+
+        let submissions = self.submissions(part);
+        if let Some(correct) = submissions.iter().find(|s| s.outcome == Outcome::Correct) {
+            return Err(Refusal::Solved {
+                part,
+                answer: correct.answer.clone(),
+            });
+        }
+        if submissions
+            .iter()
+            .any(|s| s.answer.to_string() == answer.to_string())
+        {
+            return Err(Refusal::KnownWrong {
+                answer: answer.clone(),
+            });
+        }
+
+        let Answer::Number(number) = answer else {
+            return Ok(());
+        };
+        let bound = |outcome: Outcome| {
+            submissions
+                .iter()
+                .filter(move |s| s.outcome == outcome)
+                .filter_map(|s| match s.answer {
+                    Answer::Number(number) => Some(number),
+                    Answer::Text(_) => None,
+                })
+        };
+        if let Some(bound) = bound(Outcome::TooHigh)
+            .filter(|bound| number >= bound)
+            .min()
+        {
+            return Err(Refusal::TooHigh {
+                answer: answer.clone(),
+                bound,
+            });
+        }
+        if let Some(bound) = bound(Outcome::TooLow).filter(|bound| number <= bound).max() {
+            return Err(Refusal::TooLow {
+                answer: answer.clone(),
+                bound,
+            });
+        }
+        Ok(())
+    }
+
+    /// Remembers the response of the website to an answer
+    pub fn record(&mut self, part: Part, answer: Answer, outcome: Outcome, now: u64) {
+        match outcome {
+            Outcome::Wait { seconds } => self.blocked_until = Some(now + seconds),
+            Outcome::TooHigh | Outcome::TooLow | Outcome::Wrong => {
+                self.blocked_until = Some(now + WRONG_ANSWER_WAIT)
+            }
+            _ => {}
+        }
+        if outcome.is_verdict() {
+            let submissions = match part {
+                Part::One => &mut self.part_1,
+                Part::Two => &mut self.part_2,
+            };
+            submissions.push(Submission {
+                answer,
+                outcome,
+                at: now,
+            });
+        }
+    }
+}
+
+/// Seconds since the Unix epoch
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs()
+}
+
+fn history_error(path: &Path, source: impl std::error::Error + Send + Sync + 'static) -> Error {
+    Error::History {
+        path: path.display().to_string(),
+        source: Box::new(source),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn page(message: &str) -> String {
+        format!(
+            "<html><body><main>\n<article><p>{}</p></article>\n</main></body></html>",
+            message
+        )
+    }
+
+    #[test]
+    fn parses_the_outcomes() {
+        let outcome = |message| Outcome::parse(&page(message));
+        assert_eq!(
+            outcome("That's the right answer! You are <em>one gold star</em> closer."),
+            Outcome::Correct
+        );
+        assert_eq!(
+            outcome(
+                "That's not the right answer; your answer is too high. Please wait one minute."
+            ),
+            Outcome::TooHigh
+        );
+        assert_eq!(
+            outcome("That's not the right answer; your answer is too low."),
+            Outcome::TooLow
+        );
+        assert_eq!(
+            outcome("That's not the right answer. If you're stuck, try the subreddit."),
+            Outcome::Wrong
+        );
+        assert_eq!(
+            outcome("You gave an answer too recently. You have 1m 23s left to wait."),
+            Outcome::Wait { seconds: 83 }
+        );
+        assert_eq!(
+            outcome("You don't seem to be solving the right level. Did you already complete it?"),
+            Outcome::WrongLevel
+        );
+        assert_eq!(
+            outcome("Something <b>else</b>"),
+            Outcome::Unknown("Something else".to_string())
+        );
+    }
+
+    #[test]
+    fn refuses_known_wrong_answers_and_bounds() {
+        let mut history = History::default();
+        history.record(Part::One, Answer::Number(100), Outcome::TooHigh, 0);
+        history.record(Part::One, Answer::Number(10), Outcome::TooLow, 0);
+        history.record(Part::One, Answer::Number(50), Outcome::Wrong, 0);
+        let check = |answer| history.check(Part::One, &Answer::Number(answer), 1_000);
+
+        assert_eq!(
+            check(50),
+            Err(Refusal::KnownWrong {
+                answer: Answer::Number(50)
+            })
+        );
+        assert_eq!(
+            check(120),
+            Err(Refusal::TooHigh {
+                answer: Answer::Number(120),
+                bound: 100
+            })
+        );
+        assert_eq!(
+            check(5),
+            Err(Refusal::TooLow {
+                answer: Answer::Number(5),
+                bound: 10
+            })
+        );
+        assert_eq!(check(42), Ok(()));
+        assert_eq!(
+            history.check(Part::Two, &Answer::Number(120), 1_000),
+            Ok(())
+        );
+    }
+
+    #[test]
+    fn refuses_while_blocked_and_once_solved() {
+        let mut history = History::default();
+        history.record(
+            Part::Two,
+            Answer::Number(1),
+            Outcome::Wait { seconds: 30 },
+            100,
+        );
+        assert!(history.part_2.is_empty());
+        assert_eq!(
+            history.check(Part::Two, &Answer::Number(2), 110),
+            Err(Refusal::Wait { seconds: 20 })
+        );
+
+        history.record(Part::Two, Answer::Number(2), Outcome::Correct, 200);
+        assert_eq!(
+            history.check(Part::Two, &Answer::Number(3), 300),
+            Err(Refusal::Solved {
+                part: Part::Two,
+                answer: Answer::Number(2)
+            })
+        );
+    }
+
+    #[test]
+    fn saves_the_history_as_toml() {
+        let mut history = History::default();
+        history.record(Part::One, Answer::Number(7), Outcome::TooLow, 5);
+        let text = toml::to_string(&history).unwrap();
+        assert_eq!(
+            text,
+            "blocked_until = 65\n\n[[part_1]]\nanswer = 7\noutcome = \"too_low\"\nat = 5\n"
+        );
+        assert_eq!(toml::from_str::<History>(&text).unwrap(), history);
+    }
+}